@@ -0,0 +1,192 @@
+use std::{path::Path, process::ExitCode, time::Instant};
+use util::{input::InputSource, Day, Solver};
+
+static DAYS: &[&dyn Day] = &[
+    &day1::Day1,
+    &day2::Day2,
+    &day3::Day3,
+    &day4::Day4,
+    &day5::Day5,
+];
+
+const USAGE: &str = "\
+Usage: aoc <run|bench|check> (--day <N> | --all) [--part <N>] [--variant <NAME|all>]
+           [--input <PATH> | --sample <NAME>]
+
+  run     Runs the primary solver for each part, or the chosen variant
+  bench   Measures every variant, or the chosen one
+  check   Runs every variant and verifies they agree on the answer";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Command {
+    Run,
+    Bench,
+    Check,
+}
+
+#[derive(Debug)]
+struct Args {
+    command: Command,
+    day: Option<u8>,
+    all: bool,
+    part: Option<u8>,
+    variant: Option<String>,
+    input: InputSource,
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let (input, rest) = InputSource::from_args(args)?;
+    let mut args = rest.into_iter();
+
+    let command = match args.next().as_deref() {
+        Some("run") => Command::Run,
+        Some("bench") => Command::Bench,
+        Some("check") => Command::Check,
+        Some(other) => return Err(format!("Unknown command: {other}")),
+        None => return Err("Missing command".to_string()),
+    };
+
+    let mut parsed = Args {
+        command,
+        day: None,
+        all: false,
+        part: None,
+        variant: None,
+        input: input
+            .or_else(InputSource::from_env)
+            .unwrap_or(InputSource::Default),
+    };
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or(format!("Missing value for {name}"));
+        match arg.as_str() {
+            "--day" | "-d" => parsed.day = Some(parse_number(&value(&arg)?)?),
+            "--part" | "-p" => parsed.part = Some(parse_number(&value(&arg)?)?),
+            "--variant" | "-v" => parsed.variant = Some(value(&arg)?),
+            "--all" => parsed.all = true,
+            other => return Err(format!("Unknown argument: {other}")),
+        }
+    }
+
+    if parsed.day.is_none() && !parsed.all {
+        return Err("Either --day or --all is required".to_string());
+    }
+
+    if parsed.all && matches!(parsed.input, InputSource::Path(_) | InputSource::Stdin) {
+        return Err(format!("--all cannot share the input {}", parsed.input));
+    }
+
+    Ok(parsed)
+}
+
+fn parse_number(value: &str) -> Result<u8, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Expected a number, got: {value}"))
+}
+
+// Picks the solvers to run for one part, the primary variant is the first one registered
+fn select<'s, 'a>(
+    solvers: &'s [Solver<'a>],
+    part: u8,
+    variant: Option<&str>,
+    command: Command,
+) -> Result<Vec<&'s Solver<'a>>, String> {
+    let mut for_part = solvers.iter().filter(|s| s.part == part).peekable();
+
+    match (variant, command) {
+        (Some("all"), _) | (None, Command::Bench | Command::Check) => Ok(for_part.collect()),
+        (None, Command::Run) => Ok(for_part.next().into_iter().collect()),
+        (Some(variant), _) => {
+            let available = for_part.clone().map(|s| s.variant).collect::<Vec<_>>();
+            match for_part.find(|s| s.variant == variant) {
+                Some(solver) => Ok(vec![solver]),
+                None => Err(format!(
+                    "Unknown variant {variant} for part {part}, available: {}",
+                    available.join(", ")
+                )),
+            }
+        }
+    }
+}
+
+fn run_day(day: &dyn Day, args: &Args) -> Result<(), String> {
+    let input = args
+        .input
+        .read(Path::new(day.dir()))
+        .map_err(|e| e.to_string())?;
+
+    let solvers = day
+        .solvers(&input)
+        .map_err(|e| format!("Unable to parse input for day {}: {}", day.number(), e))?;
+    let mut parts = solvers.iter().map(|s| s.part).collect::<Vec<_>>();
+    parts.sort();
+    parts.dedup();
+    if let Some(part) = args.part {
+        if !parts.contains(&part) {
+            return Err(format!("Day {} has no part {}", day.number(), part));
+        }
+        parts = vec![part];
+    }
+
+    println!("Advent of code 2024 - day {}!", day.number());
+    util::baseline::set_group(&day.name());
+
+    for part in parts {
+        let mut results = vec![];
+        for solver in select(&solvers, part, args.variant.as_deref(), args.command)? {
+            let result = match args.command {
+                Command::Run | Command::Check => {
+                    let start = Instant::now();
+                    let result = (solver.run)();
+                    let elapsed = start.elapsed();
+                    println!(
+                        "{} [{}]: {}   ({:?})",
+                        solver.label, solver.variant, result, elapsed
+                    );
+                    result
+                }
+                Command::Bench => util::measure(solver.label, &solver.run).result,
+            };
+            results.push((solver.variant, result));
+        }
+
+        util::check_agreement(part, &results)
+            .map_err(|e| format!("Day {}: {}", day.number(), e))?;
+    }
+    println!();
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}\n\n{}", util::input::USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    let days = DAYS
+        .iter()
+        .filter(|d| args.all || Some(d.number()) == args.day)
+        .collect::<Vec<_>>();
+
+    if days.is_empty() {
+        eprintln!(
+            "No solution registered for day {}",
+            args.day.unwrap_or_default()
+        );
+        return ExitCode::FAILURE;
+    }
+
+    for day in days {
+        if let Err(e) = run_day(*day, &args) {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    }
+
+    ExitCode::SUCCESS
+}
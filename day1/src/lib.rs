@@ -0,0 +1,383 @@
+use std::{
+    collections::{BTreeMap, BinaryHeap, HashMap},
+    io::BufRead,
+};
+use util::{
+    parse::Cursor,
+    stream::{for_each_line, StreamError},
+    synthetic::Rng,
+    Answer, ParseError, Solution, Variant,
+};
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u8 = 1;
+    const DIR: &'static str = env!("CARGO_MANIFEST_DIR");
+
+    type Input = Lists;
+
+    fn parse(input: &str) -> Result<Lists, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(lists: &Lists) -> Answer {
+        streaming_min_heap(lists).into()
+    }
+
+    fn part2(lists: &Lists) -> Answer {
+        similarity_hash_map(lists).into()
+    }
+
+    fn variants() -> Vec<Variant<Lists>> {
+        vec![
+            Variant::new(1, "heap", "MinHeap", streaming_min_heap),
+            Variant::new(1, "naive", "Naive", naive),
+            Variant::new(1, "radix", "LSD radix sort", radix),
+            Variant::new(1, "counting", "Counting sort", counting),
+            Variant::new(1, "histogram", "Histogram (bounded memory)", |lists| {
+                histogram_distance(&Histograms::from_lists(lists))
+            }),
+            Variant::new(
+                2,
+                "hash_map",
+                "Similarity (HashMap counts)",
+                similarity_hash_map,
+            ),
+            Variant::new(
+                2,
+                "sorted_merge",
+                "Similarity (sorted merge)",
+                similarity_sorted_merge,
+            ),
+            Variant::new(2, "dense", "Similarity (dense counts)", similarity_dense),
+        ]
+    }
+}
+
+const EXPECTED_LEN: usize = 1024;
+
+// Location IDs are 5 digits, small enough to count in an array
+const DENSE_IDS: usize = 100_000;
+
+pub struct Lists {
+    pub left: Vec<u32>,
+    pub right: Vec<u32>,
+}
+
+// A list of random 5 digit location ID pairs, for benchmarking with AOC_SYNTHETIC
+pub fn synthetic_input(lines: usize, seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let mut id = || 10_000 + rng.below(90_000);
+    (0..lines)
+        .map(|_| format!("{}   {}\n", id(), id()))
+        .collect()
+}
+
+pub fn parse_input(input: &str) -> Result<Lists, ParseError> {
+    let mut lists = Lists {
+        left: Vec::with_capacity(EXPECTED_LEN),
+        right: Vec::with_capacity(EXPECTED_LEN),
+    };
+
+    for (i, line) in input.lines().enumerate() {
+        if !line.trim().is_empty() {
+            let (left, right) = parse_line(line).map_err(|e| e.on_line(i + 1))?;
+            lists.left.push(left);
+            lists.right.push(right);
+        }
+    }
+
+    Ok(lists)
+}
+
+pub fn parse_line(line: &str) -> Result<(u32, u32), ParseError> {
+    let mut cursor = Cursor::new(line);
+
+    let mut ids = [0; 2];
+    for id in &mut ids {
+        cursor.skip_whitespace();
+        if cursor.is_at_end() {
+            return Err(cursor.error(0, "expected two location IDs per line"));
+        }
+        *id = cursor.integer()?;
+        if !cursor.peek().is_none_or(|b| b.is_ascii_whitespace()) {
+            return Err(cursor.error_here("invalid location ID"));
+        }
+    }
+
+    cursor.skip_whitespace();
+    if !cursor.is_at_end() {
+        let extra = cursor.rest().split_ascii_whitespace().next().unwrap();
+        return Err(cursor.error(extra.len(), "expected two location IDs per line"));
+    }
+
+    Ok((ids[0], ids[1]))
+}
+
+// How often each location ID occurs in either list, in ID order. This only grows with the
+// number of distinct IDs rather than with the number of lines.
+#[derive(Debug, Default)]
+pub struct Histograms {
+    pub left: BTreeMap<u32, u64>,
+    pub right: BTreeMap<u32, u64>,
+}
+
+impl Histograms {
+    pub fn from_lists(lists: &Lists) -> Self {
+        let mut histograms = Histograms::default();
+        for (left, right) in lists.left.iter().zip(&lists.right) {
+            histograms.add(*left, *right);
+        }
+        histograms
+    }
+
+    pub fn add(&mut self, left: u32, right: u32) {
+        *self.left.entry(left).or_default() += 1;
+        *self.right.entry(right).or_default() += 1;
+    }
+}
+
+// Part 1 straight from a reader, for inputs too big to load
+pub fn part1_streaming<R: BufRead>(reader: R) -> Result<u64, StreamError> {
+    let mut histograms = Histograms::default();
+    for_each_line(reader, |_, line| {
+        if !line.trim().is_empty() {
+            let (left, right) = parse_line(line)?;
+            histograms.add(left, right);
+        }
+        Ok(())
+    })?;
+
+    Ok(histogram_distance(&histograms))
+}
+
+// Pairs up the IDs in order a whole run of equal IDs at a time
+pub fn histogram_distance(histograms: &Histograms) -> u64 {
+    let mut left = histograms.left.iter().map(|(id, n)| (*id, *n));
+    let mut right = histograms.right.iter().map(|(id, n)| (*id, *n));
+
+    let mut sum = 0;
+    let (mut l, mut r) = (left.next(), right.next());
+    while let (Some((left_id, left_n)), Some((right_id, right_n))) = (l, r) {
+        let n = left_n.min(right_n);
+        sum += left_id.abs_diff(right_id) as u64 * n;
+
+        l = if left_n > n {
+            Some((left_id, left_n - n))
+        } else {
+            left.next()
+        };
+        r = if right_n > n {
+            Some((right_id, right_n - n))
+        } else {
+            right.next()
+        };
+    }
+
+    sum
+}
+
+pub fn streaming_min_heap(lists: &Lists) -> u64 {
+    let mut heap1 = BinaryHeap::with_capacity(EXPECTED_LEN);
+    let mut heap2 = BinaryHeap::with_capacity(EXPECTED_LEN);
+    heap1.extend(lists.left.iter().copied());
+    heap2.extend(lists.right.iter().copied());
+
+    let mut sum = 0;
+    while !heap1.is_empty() {
+        let (left, right) = (heap1.pop().unwrap(), heap2.pop().unwrap());
+        let diff = left.abs_diff(right);
+        sum += diff as u64;
+    }
+
+    sum
+}
+
+pub fn naive(lists: &Lists) -> u64 {
+    let (list1, list2) = sorted_lists(lists);
+    sorted_distance(&list1, &list2)
+}
+
+// Sorted copies of both lists, for the variants that walk them in order
+fn sorted_lists(lists: &Lists) -> (Vec<u32>, Vec<u32>) {
+    let mut list1 = lists.left.clone();
+    let mut list2 = lists.right.clone();
+
+    list1.sort();
+    list2.sort();
+
+    (list1, list2)
+}
+
+pub fn radix(lists: &Lists) -> u64 {
+    let mut list1 = lists.left.clone();
+    let mut list2 = lists.right.clone();
+
+    radix_sort(&mut list1);
+    radix_sort(&mut list2);
+
+    sorted_distance(&list1, &list2)
+}
+
+pub fn counting(lists: &Lists) -> u64 {
+    let mut list1 = lists.left.clone();
+    let mut list2 = lists.right.clone();
+
+    counting_sort(&mut list1);
+    counting_sort(&mut list2);
+
+    sorted_distance(&list1, &list2)
+}
+
+fn sorted_distance(list1: &[u32], list2: &[u32]) -> u64 {
+    list1
+        .iter()
+        .zip(list2.iter())
+        .fold(0, |sum, (l, r)| sum + l.abs_diff(*r) as u64)
+}
+
+// LSD radix sort a byte at a time, skipping the high bytes that are zero for every value
+pub fn radix_sort(values: &mut Vec<u32>) {
+    let max = values.iter().copied().max().unwrap_or(0);
+    let mut buffer = vec![0; values.len()];
+
+    let mut shift = 0;
+    while shift < u32::BITS && max >> shift > 0 {
+        let mut offsets = [0usize; 257];
+        for v in values.iter() {
+            offsets[((v >> shift) & 0xff) as usize + 1] += 1;
+        }
+        for i in 1..offsets.len() {
+            offsets[i] += offsets[i - 1];
+        }
+
+        for v in values.iter() {
+            let digit = ((v >> shift) & 0xff) as usize;
+            buffer[offsets[digit]] = *v;
+            offsets[digit] += 1;
+        }
+        std::mem::swap(values, &mut buffer);
+        shift += 8;
+    }
+}
+
+// Counts every possible ID and writes them back out in order, falling back to radix sort when
+// an ID is too big to count in an array
+pub fn counting_sort(values: &mut Vec<u32>) {
+    if values.iter().any(|v| *v as usize >= DENSE_IDS) {
+        return radix_sort(values);
+    }
+
+    let mut counts = vec![0u32; DENSE_IDS];
+    for v in values.iter() {
+        counts[*v as usize] += 1;
+    }
+
+    let mut i = 0;
+    for (v, count) in counts.iter().enumerate() {
+        values[i..i + *count as usize].fill(v as u32);
+        i += *count as usize;
+    }
+}
+
+pub fn similarity_hash_map(lists: &Lists) -> u64 {
+    let mut counts = HashMap::<u32, u64>::with_capacity(EXPECTED_LEN);
+    for id in &lists.right {
+        *counts.entry(*id).or_default() += 1;
+    }
+
+    lists
+        .left
+        .iter()
+        .map(|id| *id as u64 * counts.get(id).copied().unwrap_or(0))
+        .sum()
+}
+
+// Sorts the lists the same way naive does, then merges them
+pub fn similarity_sorted_merge(lists: &Lists) -> u64 {
+    let (list1, list2) = sorted_lists(lists);
+    sorted_similarity(&list1, &list2)
+}
+
+// Walks both sorted lists together, so each run of equal IDs on the right is counted once
+fn sorted_similarity(list1: &[u32], list2: &[u32]) -> u64 {
+    let mut sum = 0;
+    let mut j = 0;
+    let mut last: Option<(u32, u64)> = None;
+    for id in list1 {
+        let count = match last {
+            // Same ID as before, the right list has already moved past its run
+            Some((last_id, count)) if last_id == *id => count,
+            _ => {
+                while j < list2.len() && list2[j] < *id {
+                    j += 1;
+                }
+                let start = j;
+                while j < list2.len() && list2[j] == *id {
+                    j += 1;
+                }
+                (j - start) as u64
+            }
+        };
+        last = Some((*id, count));
+        sum += *id as u64 * count;
+    }
+
+    sum
+}
+
+pub fn similarity_dense(lists: &Lists) -> u64 {
+    if lists.right.iter().any(|id| *id as usize >= DENSE_IDS) {
+        return similarity_hash_map(lists);
+    }
+
+    let mut counts = vec![0u32; DENSE_IDS];
+    for id in &lists.right {
+        counts[*id as usize] += 1;
+    }
+
+    lists
+        .left
+        .iter()
+        .map(|id| *id as u64 * counts.get(*id as usize).copied().unwrap_or(0) as u64)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    util::answer_tests! {
+        Day1,
+        sample: "input_sample.txt" => [1: 11, 2: 31],
+        input: "input.txt" => [1: 936063, 2: 23150395],
+    }
+
+    #[test]
+    fn test_sorts() {
+        let mut rng = Rng::new(1);
+        for max in [1, 255, 256, DENSE_IDS as u64, u32::MAX as u64] {
+            let values = (0..1000).map(|_| rng.below(max) as u32).collect::<Vec<_>>();
+            let mut expected = values.clone();
+            expected.sort();
+
+            let (mut radix, mut counting) = (values.clone(), values);
+            radix_sort(&mut radix);
+            counting_sort(&mut counting);
+            assert_eq!(radix, expected, "max {max}");
+            assert_eq!(counting, expected, "max {max}");
+        }
+    }
+
+    #[test]
+    fn test_streaming() {
+        let input = std::fs::read_to_string(format!("{}/input.txt", Day1::DIR)).unwrap();
+        assert_eq!(part1_streaming(input.as_bytes()).unwrap(), 936063);
+
+        match part1_streaming("3 4\n4 3 1\n".as_bytes()) {
+            Err(StreamError::Parse(e)) => assert_eq!((e.line, e.column), (2, 5)),
+            other => panic!("Expected a parse error, got {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,758 @@
+use std::{fmt, io::BufRead, ops::RangeInclusive, sync::OnceLock};
+use util::{
+    bitset::Bitset,
+    parse::Cursor,
+    stream::{for_each_line, StreamError},
+    Answer, ParseError, Solution, Variant,
+};
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: u8 = 2;
+    const DIR: &'static str = env!("CARGO_MANIFEST_DIR");
+
+    type Input = Vec<Vec<i32>>;
+
+    fn parse(input: &str) -> Result<Vec<Vec<i32>>, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(levels: &Vec<Vec<i32>>) -> Answer {
+        count_safe(levels, is_safe).into()
+    }
+
+    fn part2(levels: &Vec<Vec<i32>>) -> Answer {
+        count_safe(levels, is_safe_with_dampener_naive).into()
+    }
+
+    fn variants() -> Vec<Variant<Vec<Vec<i32>>>> {
+        #[allow(unused_mut)]
+        let mut variants = vec![
+            Variant::new(1, "default", "Num safe (part1)", Self::part1),
+            Variant::new(
+                1,
+                "k_removals",
+                "Num safe (part1 with up to k removals)",
+                |levels: &Vec<Vec<i32>>| {
+                    count_safe(levels, |l| is_safe_with_k_removals(l, 0, SAFE_DELTAS))
+                },
+            ),
+            Variant::new(2, "naive", "Num safe (part 2 naive)", Self::part2),
+            Variant::new(
+                2,
+                "bitmasks",
+                "Num safe (part 2 with bitmasks, single pass past 17 levels)",
+                |levels: &Vec<Vec<i32>>| {
+                    count_safe(levels, |l| is_safe_with_dampener_masks(l, &MASKS))
+                },
+            ),
+            Variant::new(
+                2,
+                "bitmasks_u128",
+                "Num safe (part 2 with u128 bitmasks, single pass past 65 levels)",
+                |levels: &Vec<Vec<i32>>| {
+                    count_safe(levels, |l| is_safe_with_dampener_masks(l, &MASKS_U128))
+                },
+            ),
+            Variant::new(
+                2,
+                "bitmasks_any_len",
+                "Num safe (part 2 with Vec<u64> bitmasks)",
+                |levels: &Vec<Vec<i32>>| {
+                    count_safe(levels, |l| is_safe_with_dampener_masks(l, &MASKS_ANY_LEN))
+                },
+            ),
+            Variant::new(
+                2,
+                "single_pass",
+                "Num safe (part 2 single pass)",
+                |levels: &Vec<Vec<i32>>| {
+                    count_safe(levels, |l| {
+                        is_safe_with_dampener_single_pass(l.iter().copied())
+                    })
+                },
+            ),
+            Variant::new(
+                2,
+                "k_removals",
+                "Num safe (part 2 with up to k removals)",
+                |levels: &Vec<Vec<i32>>| {
+                    count_safe(levels, |l| is_safe_with_k_removals(l, 1, SAFE_DELTAS))
+                },
+            ),
+            Variant::new(
+                2,
+                "explain",
+                "Num safe (part 2 explained)",
+                |levels: &Vec<Vec<i32>>| count_safe(levels, |l| explain(l).is_safe()),
+            ),
+        ];
+
+        #[cfg(feature = "parallel")]
+        variants.extend([
+            Variant::new(
+                1,
+                "parallel",
+                "Num safe (part1, parallel)",
+                |levels: &Vec<Vec<i32>>| count_safe_parallel(levels, is_safe),
+            ),
+            Variant::new(
+                2,
+                "bitmasks_parallel",
+                "Num safe (part 2 with bitmasks, parallel, single pass past 17 levels)",
+                |levels: &Vec<Vec<i32>>| {
+                    count_safe_parallel(levels, |l| is_safe_with_dampener_masks(l, &MASKS))
+                },
+            ),
+        ]);
+
+        variants
+    }
+}
+
+// How much adjacent levels may differ by in a safe report
+pub const SAFE_DELTAS: RangeInclusive<u32> = 1..=3;
+
+static MASKS: MaskTable<u32> = MaskTable::new();
+static MASKS_U128: MaskTable<u128> = MaskTable::new();
+static MASKS_ANY_LEN: MaskTable<Vec<u64>> = MaskTable::new();
+
+pub fn parse_input(input: &str) -> Result<Vec<Vec<i32>>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| split_line_to_levels(line).map_err(|e| e.on_line(i + 1)))
+        .collect()
+}
+
+// Both parts straight from a reader a line at a time, for inputs too big to load
+pub fn count_safe_streaming<R: BufRead>(reader: R) -> Result<(usize, usize), StreamError> {
+    let (mut safe, mut safe_with_dampener) = (0, 0);
+    for_each_line(reader, |_, line| {
+        let levels = split_line_to_levels(line)?;
+        if is_safe(&levels) {
+            safe += 1;
+        }
+
+        if is_safe_with_dampener_single_pass(levels.iter().copied()) {
+            safe_with_dampener += 1;
+        }
+        Ok(())
+    })?;
+
+    Ok((safe, safe_with_dampener))
+}
+
+pub fn count_safe<F: Fn(&[i32]) -> bool>(levels: &[Vec<i32>], is_safe: F) -> usize {
+    levels
+        .iter()
+        .fold(0, |acc, l| acc + if is_safe(l) { 1 } else { 0 })
+}
+
+// Splits the reports into one band of lines per thread
+#[cfg(feature = "parallel")]
+pub fn count_safe_parallel<F: Fn(&[i32]) -> bool + Sync>(levels: &[Vec<i32>], is_safe: F) -> usize {
+    util::parallel::map_chunks(levels, |chunk| count_safe(chunk, &is_safe))
+        .into_iter()
+        .sum()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Asc,
+    Desc,
+    None,
+}
+
+fn get_direction_and_diff(a: i32, b: i32) -> (Direction, i32) {
+    let diff = a.abs_diff(b);
+    #[allow(clippy::comparison_chain)]
+    if a > b {
+        (Direction::Desc, diff as i32)
+    } else if a < b {
+        (Direction::Asc, diff as i32)
+    } else {
+        (Direction::None, 0)
+    }
+}
+
+fn is_safe_transition(a: i32, b: i32, expected_direction: Option<Direction>) -> (bool, Direction) {
+    let (direction, diff) = get_direction_and_diff(a, b);
+
+    let is_safe = match direction {
+        Direction::None => false,
+        Direction::Asc | Direction::Desc => {
+            (expected_direction.is_none() || direction == expected_direction.unwrap())
+                && SAFE_DELTAS.contains(&(diff as u32))
+        }
+    };
+
+    //println!("{} -> {} ({:?}) : {}", a, b, direction, if is_safe { "safe"} else { "unsafe"} );
+
+    (is_safe, direction)
+}
+
+pub fn split_line_to_levels(line: &str) -> Result<Vec<i32>, ParseError> {
+    let mut cursor = Cursor::new(line);
+    let mut levels = vec![];
+    loop {
+        cursor.skip_whitespace();
+        if cursor.is_at_end() {
+            break;
+        }
+        levels.push(cursor.integer()?);
+        if !cursor.peek().is_none_or(|b| b.is_ascii_whitespace()) {
+            return Err(cursor.error_here("invalid level"));
+        }
+    }
+
+    if levels.len() < 2 {
+        return Err(ParseError::at_end(
+            line,
+            "a report needs at least two levels",
+        ));
+    }
+
+    Ok(levels)
+}
+
+pub fn is_safe_with_dampener_naive(levels: &[i32]) -> bool {
+    if is_safe(levels) {
+        return true;
+    }
+
+    for i in 0..levels.len() {
+        let mut variant: Vec<i32> = levels.into();
+        variant.remove(i);
+
+        if is_safe(&variant) {
+            return true;
+        }
+    }
+
+    false
+}
+
+pub fn is_safe(levels: &[i32]) -> bool {
+    // Without a transition there is nothing to be unsafe, e.g. a two level report with a level
+    // removed by the dampener
+    if levels.len() < 2 {
+        return true;
+    }
+
+    let (is_safe, expected_direction) = is_safe_transition(levels[0], levels[1], None);
+
+    if !is_safe {
+        return false;
+    }
+
+    for i in 1..levels.len() - 1 {
+        let (safe, _) = is_safe_transition(levels[i], levels[i + 1], Some(expected_direction));
+        if !safe {
+            return false;
+        }
+    }
+
+    true
+}
+
+// Why a transition between two levels is unsafe
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Problem {
+    // Increasing when the report started out decreasing, or the other way around
+    DirectionFlip,
+    // The same level twice in a row
+    ZeroDelta,
+    // A change outside of SAFE_DELTAS, by how much
+    DeltaTooSmall(u32),
+    DeltaTooLarge(u32),
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Problem::DirectionFlip => write!(f, "direction flip"),
+            Problem::ZeroDelta => write!(f, "zero delta"),
+            Problem::DeltaTooSmall(delta) => {
+                write!(f, "delta of {delta} < {}", SAFE_DELTAS.start())
+            }
+            Problem::DeltaTooLarge(delta) => write!(f, "delta of {delta} > {}", SAFE_DELTAS.end()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SafetyReport {
+    Safe,
+    // Safe once the Problem Dampener removes the level at this index
+    SafeWithout(usize),
+    // Unsafe even with the dampener, index is where the first offending transition of the whole
+    // report starts
+    Unsafe { index: usize, problem: Problem },
+}
+
+impl SafetyReport {
+    pub fn is_safe(&self) -> bool {
+        !matches!(self, SafetyReport::Unsafe { .. })
+    }
+}
+
+impl fmt::Display for SafetyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SafetyReport::Safe => write!(f, "safe"),
+            SafetyReport::SafeWithout(i) => write!(f, "safe after removing the level at {i}"),
+            SafetyReport::Unsafe { index, problem } => {
+                write!(f, "unsafe, {problem} from {index} to {}", index + 1)
+            }
+        }
+    }
+}
+
+// Like is_safe_with_dampener_naive, but says which level was removed or why it is unsafe
+pub fn explain(levels: &[i32]) -> SafetyReport {
+    let Some((index, problem)) = first_problem(levels, None) else {
+        return SafetyReport::Safe;
+    };
+
+    match (0..levels.len()).find(|i| first_problem(levels, Some(*i)).is_none()) {
+        Some(i) => SafetyReport::SafeWithout(i),
+        None => SafetyReport::Unsafe { index, problem },
+    }
+}
+
+// The first unsafe transition, skipping the level at skip, as the index of the level it starts
+// from and what is wrong with it
+fn first_problem(levels: &[i32], skip: Option<usize>) -> Option<(usize, Problem)> {
+    let mut kept = levels.iter().enumerate().filter(|(i, _)| Some(*i) != skip);
+    let mut previous = kept.next()?;
+    let mut expected_direction = None;
+
+    for current in kept {
+        let (index, a, b) = (previous.0, *previous.1, *current.1);
+        let (direction, diff) = get_direction_and_diff(a, b);
+
+        let problem = if direction == Direction::None {
+            Some(Problem::ZeroDelta)
+        } else if expected_direction.is_some_and(|d| d != direction) {
+            Some(Problem::DirectionFlip)
+        } else if (diff as u32) < *SAFE_DELTAS.start() {
+            Some(Problem::DeltaTooSmall(diff as u32))
+        } else if (diff as u32) > *SAFE_DELTAS.end() {
+            Some(Problem::DeltaTooLarge(diff as u32))
+        } else {
+            None
+        };
+        if let Some(problem) = problem {
+            return Some((index, problem));
+        }
+
+        expected_direction = Some(direction);
+        previous = current;
+    }
+
+    None
+}
+
+// Whether the report is safe after removing up to k levels, with the levels going up or down by
+// an amount in deltas every step. The longest chain of levels that can be kept ending at each
+// level only depends on the k + 1 levels before it, so this is O(n * k).
+pub fn is_safe_with_k_removals(levels: &[i32], k: usize, deltas: RangeInclusive<u32>) -> bool {
+    if levels.len() <= k + 1 {
+        return true;
+    }
+
+    let ascending = |a: i32, b: i32| b.checked_sub(a).and_then(|d| u32::try_from(d).ok());
+    let descending = |a: i32, b: i32| a.checked_sub(b).and_then(|d| u32::try_from(d).ok());
+    let steps: [&dyn Fn(i32, i32) -> Option<u32>; 2] = [&ascending, &descending];
+
+    let mut chain = vec![0; levels.len()];
+    for step in steps {
+        for i in 0..levels.len() {
+            // Only up to k levels can be dropped between two kept ones
+            chain[i] = 1
+                + (i.saturating_sub(k + 1)..i)
+                    .filter(|j| step(levels[*j], levels[i]).is_some_and(|d| deltas.contains(&d)))
+                    .map(|j| chain[j])
+                    .max()
+                    .unwrap_or(0);
+
+            if chain[i] + k >= levels.len() {
+                return true;
+            }
+        }
+    }
+
+    false
+}
+
+// Checks a report with the Problem Dampener in one pass over its levels, so it works on
+// reports of any length without needing them all at once
+pub fn is_safe_with_dampener_single_pass(levels: impl IntoIterator<Item = i32>) -> bool {
+    let mut ascending = Dampener::new(1);
+    let mut descending = Dampener::new(-1);
+    for level in levels {
+        ascending.push(level);
+        descending.push(level);
+        if !ascending.is_safe() && !descending.is_safe() {
+            return false;
+        }
+    }
+    true
+}
+
+// Follows the levels seen so far in one direction. With a level removed, the last level kept is
+// either the one before the latest (the latest was removed) or the latest one, so only those two
+// have to be tracked.
+#[derive(Debug, Clone)]
+struct Dampener {
+    sign: i32,
+    // Last level when nothing had to be removed
+    intact: Option<i32>,
+    // Possible last levels when one level was removed
+    dampened: [Option<i32>; 2],
+    // Nothing seen yet, so removing the first level is still possible
+    started: bool,
+}
+
+impl Dampener {
+    fn new(sign: i32) -> Self {
+        Dampener {
+            sign,
+            intact: None,
+            dampened: [None, None],
+            started: false,
+        }
+    }
+
+    fn fits(&self, a: i32, b: i32) -> bool {
+        b.checked_sub(a)
+            .and_then(|d| d.checked_mul(self.sign))
+            .and_then(|d| u32::try_from(d).ok())
+            .is_some_and(|d| SAFE_DELTAS.contains(&d))
+    }
+
+    fn push(&mut self, level: i32) {
+        if !self.started {
+            self.started = true;
+            self.intact = Some(level);
+            return;
+        }
+
+        // Removing the very first level lets the second one start the dampened run
+        let first_removed = self.intact.is_some() && self.dampened == [None, None];
+        let continues = first_removed
+            || self
+                .dampened
+                .iter()
+                .flatten()
+                .any(|kept| self.fits(*kept, level));
+
+        self.dampened = [self.intact, continues.then_some(level)];
+        self.intact = self
+            .intact
+            .filter(|last| self.fits(*last, level))
+            .map(|_| level);
+    }
+
+    fn is_safe(&self) -> bool {
+        self.intact.is_some() || self.dampened.iter().any(Option::is_some)
+    }
+}
+
+pub fn generate_masks<B: Bitset>(num_delta_levels: usize) -> Vec<Vec<B>> {
+    (0..num_delta_levels).map(masks_for_deltas).collect()
+}
+
+// The masks for a report with level deltas, i.e. level + 1 levels
+pub fn masks_for_deltas<B: Bitset>(level: usize) -> Vec<B> {
+    if level == 0 {
+        return vec![];
+    }
+
+    // Single level undampened, i.e. all values from 0..num_levels-1
+    let mut single_level_mask = B::with_capacity(2 * level - 1);
+    (0..level).for_each(|i| single_level_mask.set(i));
+    let without = |skip: &[usize], extra: Option<usize>| {
+        let mut mask = B::with_capacity(2 * level - 1);
+        (0..level)
+            .filter(|i| !skip.contains(i))
+            .chain(extra)
+            .for_each(|i| mask.set(i));
+        mask
+    };
+
+    let mut masks = vec![
+        single_level_mask,
+        // Dampened with first element removed
+        without(&[0], None),
+        without(&[level - 1], None),
+    ];
+
+    for i in 1..level {
+        // Remove ith and i-1th element, replace with i+num_delta_levels element
+        masks.push(without(&[i - 1, i], Some(i + level - 1)));
+    }
+
+    masks
+}
+
+// Mask tables by report length, each generated the first time a report that long comes up.
+// Lengths are grouped in buckets of doubling size so the table can grow without locking, and
+// looking up a table that is already there is only a couple of loads.
+pub struct MaskTable<B> {
+    buckets: [OnceLock<Bucket<B>>; usize::BITS as usize],
+}
+
+// The tables for a run of lengths, each filled in when first needed
+type Bucket<B> = Box<[OnceLock<Vec<B>>]>;
+
+impl<B: Bitset> MaskTable<B> {
+    pub const fn new() -> Self {
+        MaskTable {
+            buckets: [const { OnceLock::new() }; usize::BITS as usize],
+        }
+    }
+
+    pub fn get(&self, len: usize) -> &[B] {
+        // Bucket i holds the 2^i lengths starting at 2^i - 1
+        let bucket = (len + 1).ilog2() as usize;
+        let slots = self.buckets[bucket]
+            .get_or_init(|| (0..1usize << bucket).map(|_| OnceLock::new()).collect());
+        slots[len + 1 - (1 << bucket)].get_or_init(|| masks_for_deltas(len.saturating_sub(1)))
+    }
+}
+
+impl<B: Bitset> Default for MaskTable<B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// The bitmask check with masks from table for reports that fit in B. Wider reports, and ones
+// too short to have a delta, are checked in a single pass instead.
+pub fn is_safe_with_dampener_masks<B: Bitset>(levels: &[i32], table: &MaskTable<B>) -> bool {
+    if levels.len() < 2 || 2 * levels.len() - 3 > B::MAX_BITS {
+        return is_safe_with_dampener_single_pass(levels.iter().copied());
+    }
+    is_safe_with_dampener_bitmasks(levels, table.get(levels.len()))
+}
+
+pub fn is_safe_with_dampener_bitmasks<B: Bitset>(levels: &[i32], masks: &[B]) -> bool {
+    assert_eq!(masks.len(), levels.len() + 1);
+
+    // Calculate deltas for i - i+1 and also i - i+2
+    let num_delta_levels = levels.len() - 1;
+    let mut valid = B::with_capacity(2 * num_delta_levels - 1);
+    let mut sign = B::with_capacity(2 * num_delta_levels - 1);
+
+    for i in 0..num_delta_levels {
+        // Single level delta
+        let (a, b) = (levels[i], levels[i + 1]);
+        if SAFE_DELTAS.contains(&a.abs_diff(b)) {
+            valid.set(i);
+        }
+        if a > b {
+            sign.set(i);
+        }
+
+        // Double level delta
+        if i < num_delta_levels - 1 {
+            let (a, b) = (levels[i], levels[i + 2]);
+            if SAFE_DELTAS.contains(&a.abs_diff(b)) {
+                valid.set(i + num_delta_levels);
+            }
+            if a > b {
+                sign.set(i + num_delta_levels);
+            }
+        }
+    }
+
+    masks.iter().any(|mask| {
+        valid.contains_all(mask) && (sign.contains_none(mask) || sign.contains_all(mask))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    util::answer_tests! {
+        Day2,
+        sample: "input_sample.txt" => [1: 2, 2: 4],
+        input: "input.txt" => [1: 463, 2: 514],
+        short: "input_sample_short.txt" => [1: 1, 2: 4],
+        // Reports of 66 to 200 levels, wider than the u32 and u128 masks can hold. Only
+        // bitmasks_any_len checks these with masks, bitmasks and bitmasks_u128 fall back to the
+        // single pass check.
+        wide: "input_sample_wide.txt" => [1: 12, 2: 14],
+    }
+
+    #[test]
+    fn test_explain() {
+        let report = |line: &str| explain(&split_line_to_levels(line).unwrap());
+        let unsafe_at = |index, problem| SafetyReport::Unsafe { index, problem };
+
+        assert_eq!(report("7 6 4 2 1"), SafetyReport::Safe);
+        assert_eq!(report("1 2 7 8 9"), unsafe_at(1, Problem::DeltaTooLarge(5)));
+        assert_eq!(report("9 7 6 2 1"), unsafe_at(2, Problem::DeltaTooLarge(4)));
+        assert_eq!(
+            unsafe_at(2, Problem::DeltaTooLarge(4)).to_string(),
+            "unsafe, delta of 4 > 3 from 2 to 3"
+        );
+        // Deltas too big to fit in an i32 are still too large
+        assert_eq!(
+            explain(&[i32::MIN, i32::MAX, i32::MAX - 1]),
+            SafetyReport::SafeWithout(0)
+        );
+        assert_eq!(
+            first_problem(&[i32::MIN, i32::MAX], None),
+            Some((0, Problem::DeltaTooLarge(u32::MAX)))
+        );
+        assert_eq!(report("1 3 2 4 5"), SafetyReport::SafeWithout(1));
+        assert_eq!(report("8 6 4 4 1"), SafetyReport::SafeWithout(2));
+        assert_eq!(report("1 3 6 7 9"), SafetyReport::Safe);
+        assert_eq!(report("5 5 6 5 4"), unsafe_at(0, Problem::ZeroDelta));
+        assert_eq!(report("1 2 1 2 1"), unsafe_at(1, Problem::DirectionFlip));
+    }
+
+    #[test]
+    fn test_k_removals() {
+        // Tries every way of removing up to k levels
+        fn brute_force(levels: &[i32], k: usize, deltas: RangeInclusive<u32>) -> bool {
+            (0u32..1 << levels.len())
+                .filter(|removed| removed.count_ones() as usize <= k)
+                .any(|removed| {
+                    let kept = (0..levels.len())
+                        .filter(|i| removed & (1 << i) == 0)
+                        .map(|i| levels[i])
+                        .collect::<Vec<_>>();
+                    let steps = kept.windows(2).map(|w| w[1] - w[0]).collect::<Vec<_>>();
+                    let fits = |d: i32| deltas.contains(&d.unsigned_abs());
+                    steps.iter().all(|d| *d >= 0 && fits(*d))
+                        || steps.iter().all(|d| *d <= 0 && fits(*d))
+                })
+        }
+
+        let mut rng = util::synthetic::Rng::new(2);
+        for _ in 0..2000 {
+            let len = 2 + rng.below(7) as usize;
+            let levels = (0..len).map(|_| rng.below(12) as i32).collect::<Vec<_>>();
+            for k in 0..3 {
+                for deltas in [1..=3, 0..=2, 2..=5] {
+                    assert_eq!(
+                        is_safe_with_k_removals(&levels, k, deltas.clone()),
+                        brute_force(&levels, k, deltas.clone()),
+                        "{levels:?} k = {k} deltas = {deltas:?}"
+                    );
+                }
+            }
+            assert_eq!(
+                is_safe_with_k_removals(&levels, 1, SAFE_DELTAS),
+                is_safe_with_dampener_naive(&levels)
+            );
+        }
+    }
+
+    #[test]
+    fn test_single_pass() {
+        let mut rng = util::synthetic::Rng::new(3);
+        for _ in 0..2000 {
+            // Mostly safe steps, with the odd bad one thrown in
+            let len = 2 + rng.below(300) as usize;
+            let mut level = 0;
+            let levels = (0..len)
+                .map(|_| {
+                    level += match rng.below(len as u64) {
+                        0 => rng.below(10) as i32 - 5,
+                        _ => 1 + rng.below(3) as i32,
+                    };
+                    level
+                })
+                .collect::<Vec<_>>();
+
+            assert_eq!(
+                is_safe_with_dampener_single_pass(levels.iter().copied()),
+                is_safe_with_k_removals(&levels, 1, SAFE_DELTAS),
+                "{levels:?}"
+            );
+        }
+
+        assert!(is_safe_with_dampener_single_pass([5, 5]));
+        assert!(is_safe_with_dampener_single_pass([i32::MAX, i32::MIN]));
+        assert!(!is_safe_with_dampener_single_pass([i32::MIN, i32::MAX, 0]));
+        assert!(is_safe_with_dampener_single_pass([i32::MIN, 0, 1, 2]));
+        assert!(is_safe_with_dampener_bitmasks(&[5, 5], MASKS.get(2)));
+        assert!(is_safe_with_dampener_masks(&[5], &MASKS));
+        assert!(is_safe_with_dampener_single_pass([9, 1, 2, 3]));
+        assert!(!is_safe_with_dampener_single_pass([9, 1, 2, 3, 0]));
+    }
+
+    #[test]
+    fn test_wide_bitmasks() {
+        // Deltas that overflow an i32
+        for levels in [[i32::MIN, i32::MAX, 5], [i32::MAX, i32::MIN, 0]] {
+            assert!(!is_safe_with_dampener_bitmasks(&levels, MASKS.get(3)));
+            assert!(!is_safe_with_dampener_bitmasks(&levels, MASKS_U128.get(3)));
+            assert!(!is_safe_with_dampener_bitmasks(
+                &levels,
+                MASKS_ANY_LEN.get(3)
+            ));
+        }
+        let levels = [i32::MIN, 1, 2];
+        assert!(is_safe_with_dampener_bitmasks(&levels, MASKS.get(3)));
+        assert!(is_safe_with_dampener_bitmasks(
+            &levels,
+            MASKS_ANY_LEN.get(3)
+        ));
+
+        let mut rng = util::synthetic::Rng::new(4);
+        for len in [2, 3, 4, 17, 40, 65, 200] {
+            for _ in 0..50 {
+                let mut levels = (0..len).map(|i| i * 2).collect::<Vec<i32>>();
+                for _ in 0..rng.below(3) {
+                    let level = rng.below(500) as i32;
+                    levels[rng.below(len as u64) as usize] =
+                        *rng.choose(&[level, i32::MIN, i32::MAX]);
+                }
+
+                let expected = is_safe_with_dampener_single_pass(levels.iter().copied());
+                let any_len = MASKS_ANY_LEN.get(len as usize);
+                assert_eq!(is_safe_with_dampener_bitmasks(&levels, any_len), expected);
+                assert_eq!(is_safe_with_dampener_masks(&levels, &MASKS), expected);
+                assert_eq!(is_safe_with_dampener_masks(&levels, &MASKS_U128), expected);
+                if len <= 65 {
+                    let masks = masks_for_deltas::<[u64; 2]>(len as usize - 1);
+                    assert_eq!(is_safe_with_dampener_bitmasks(&levels, &masks), expected);
+                }
+                if len <= 17 {
+                    let masks = MASKS.get(len as usize);
+                    assert_eq!(is_safe_with_dampener_bitmasks(&levels, masks), expected);
+                }
+            }
+        }
+
+        // The same tables the fixed size version used to generate
+        assert_eq!(generate_masks::<u32>(5)[4], [15, 14, 7, 28, 41, 67]);
+    }
+
+    #[test]
+    fn test_streaming() {
+        let input = std::fs::read_to_string(format!("{}/input.txt", Day2::DIR)).unwrap();
+        assert_eq!(count_safe_streaming(input.as_bytes()).unwrap(), (463, 514));
+
+        // Longer than the masks go
+        let long = (1..=20)
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        assert_eq!(count_safe_streaming(long.as_bytes()).unwrap(), (1, 1));
+
+        let short = std::fs::read_to_string(format!("{}/input_sample_short.txt", Day2::DIR));
+        assert_eq!(
+            count_safe_streaming(short.unwrap().as_bytes()).unwrap(),
+            (1, 4)
+        );
+    }
+}
@@ -0,0 +1,642 @@
+#[cfg(feature = "regex")]
+use regex::Regex;
+#[cfg(feature = "regex")]
+use std::sync::LazyLock;
+use std::{fmt, ops::Range};
+use util::{parse, Answer, ParseError, Solution, Variant};
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: u8 = 3;
+    const DIR: &'static str = env!("CARGO_MANIFEST_DIR");
+
+    type Input = String;
+
+    fn parse(input: &str) -> Result<String, ParseError> {
+        Ok(input.to_string())
+    }
+
+    // Tokenizing and parsing are part of the run, so the interpreter is timed on the same work
+    // as the scanner and regex
+    fn part1(input: &String) -> Answer {
+        run(&parse_program(input), Accumulator::default())
+            .total
+            .into()
+    }
+
+    fn part2(input: &String) -> Answer {
+        run(&parse_program(input), Conditional::default())
+            .total
+            .into()
+    }
+
+    fn variants() -> Vec<Variant<String>> {
+        #[allow(unused_mut)]
+        let mut variants = vec![
+            Variant::new(1, "interpreter", "Part 1 (interpreter)", Self::part1),
+            Variant::new(1, "scanner", "Part 1 (scanner)", |input: &String| {
+                scan(input, Accumulator::default()).total
+            }),
+            Variant::new(2, "interpreter", "Part 2 (interpreter)", Self::part2),
+            Variant::new(
+                2,
+                "scanner",
+                "Part 2 (scanner with do/don't)",
+                |input: &String| scan(input, Conditional::default()).total,
+            ),
+        ];
+        #[cfg(feature = "regex")]
+        variants.extend([
+            Variant::new(1, "regex", "Part 1 (regex)", |input: &String| part1(input)),
+            Variant::new(
+                2,
+                "regex",
+                "Part 2 (regex with do/don't)",
+                |input: &String| part2(input),
+            ),
+        ]);
+        variants
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    // A run of letters and apostrophes, e.g. xmul or don't
+    Word,
+    Number,
+    OpenParen,
+    CloseParen,
+    Comma,
+    // A run of anything else, which breaks up instructions
+    Garbage,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    // Byte range of the token in the input
+    pub span: Range<usize>,
+}
+
+pub fn tokenize(input: &str) -> Vec<Token> {
+    let bytes = input.as_bytes();
+    let kind_of = |b: u8| match b {
+        b'a'..=b'z' | b'A'..=b'Z' | b'\'' => TokenKind::Word,
+        b'0'..=b'9' => TokenKind::Number,
+        b'(' => TokenKind::OpenParen,
+        b')' => TokenKind::CloseParen,
+        b',' => TokenKind::Comma,
+        _ => TokenKind::Garbage,
+    };
+
+    let mut tokens = vec![];
+    let mut start = 0;
+    while start < bytes.len() {
+        let kind = kind_of(bytes[start]);
+        let mut end = start + 1;
+        // Punctuation is always a token of its own
+        if matches!(
+            kind,
+            TokenKind::Word | TokenKind::Number | TokenKind::Garbage
+        ) {
+            while end < bytes.len() && kind_of(bytes[end]) == kind {
+                end += 1;
+            }
+        }
+        tokens.push(Token {
+            kind,
+            span: start..end,
+        });
+        start = end;
+    }
+
+    tokens
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Mul(i32, i32),
+    Do,
+    Dont,
+}
+
+impl Instruction {
+    // Every instruction name, a word ending in one of these followed by arguments is a call
+    pub const NAMES: &[&str] = &["mul", "do", "don't"];
+
+    // Arguments are at most this many digits, anything longer is corrupted
+    pub const MAX_DIGITS: usize = 3;
+
+    // The instruction called name with args, None if there is none taking that many arguments
+    pub fn decode(name: &str, args: &[i32]) -> Option<Instruction> {
+        match (name, args) {
+            ("mul", [a, b]) => Some(Instruction::Mul(*a, *b)),
+            ("do", []) => Some(Instruction::Do),
+            ("don't", []) => Some(Instruction::Dont),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Statement {
+    pub instruction: Instruction,
+    // Byte range of the whole call in the input, from its name to the closing parenthesis
+    pub span: Range<usize>,
+}
+
+pub type Program = Vec<Statement>;
+
+// Picks out every well formed call among the garbage, so this never fails
+pub fn parse_program(input: &str) -> Program {
+    let tokens = tokenize(input);
+    let mut program = vec![];
+    let mut args = vec![];
+
+    for (i, token) in tokens.iter().enumerate() {
+        if token.kind != TokenKind::Word {
+            continue;
+        }
+        args.clear();
+        let Some(end) = parse_args(input, &tokens[i + 1..], &mut args) else {
+            continue;
+        };
+
+        let word = &input[token.span.clone()];
+        let call = Instruction::NAMES
+            .iter()
+            .filter(|name| word.ends_with(**name))
+            .find_map(|name| {
+                let instruction = Instruction::decode(name, &args)?;
+                Some(Statement {
+                    instruction,
+                    span: token.span.end - name.len()..end,
+                })
+            });
+        program.extend(call);
+    }
+
+    program
+}
+
+// Reads (a,b,...) from the start of tokens into args, returning where it ends in the input
+fn parse_args(input: &str, tokens: &[Token], args: &mut Vec<i32>) -> Option<usize> {
+    let mut tokens = tokens.iter();
+    if tokens.next()?.kind != TokenKind::OpenParen {
+        return None;
+    }
+
+    let mut token = tokens.next()?;
+    if token.kind != TokenKind::CloseParen {
+        loop {
+            if token.kind != TokenKind::Number || token.span.len() > Instruction::MAX_DIGITS {
+                return None;
+            }
+            args.push(parse::integer(&input[token.span.clone()]).ok()?);
+
+            token = tokens.next()?;
+            match token.kind {
+                TokenKind::Comma => token = tokens.next()?,
+                TokenKind::CloseParen => break,
+                _ => return None,
+            }
+        }
+    }
+
+    Some(token.span.end)
+}
+
+// What a program runs on, so the same program can mean different things
+pub trait State {
+    fn execute(&mut self, instruction: Instruction);
+}
+
+pub fn run<S: State>(program: &[Statement], mut state: S) -> S {
+    for statement in program {
+        state.execute(statement.instruction);
+    }
+    state
+}
+
+// Adds up every multiplication, ignoring do and don't
+#[derive(Debug, Default)]
+pub struct Accumulator {
+    pub total: i32,
+}
+
+impl State for Accumulator {
+    fn execute(&mut self, instruction: Instruction) {
+        if let Instruction::Mul(a, b) = instruction {
+            self.total += a * b;
+        }
+    }
+}
+
+// Only adds up multiplications while enabled, which don't turns off until the next do
+#[derive(Debug)]
+pub struct Conditional {
+    pub enabled: bool,
+    pub total: i32,
+}
+
+impl Default for Conditional {
+    fn default() -> Self {
+        Conditional {
+            enabled: true,
+            total: 0,
+        }
+    }
+}
+
+impl State for Conditional {
+    fn execute(&mut self, instruction: Instruction) {
+        match instruction {
+            Instruction::Mul(a, b) if self.enabled => self.total += a * b,
+            Instruction::Mul(..) => {}
+            Instruction::Do => self.enabled = true,
+            Instruction::Dont => self.enabled = false,
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Instruction::Mul(a, b) => write!(f, "mul({a},{b})"),
+            Instruction::Do => write!(f, "do()"),
+            Instruction::Dont => write!(f, "don't()"),
+        }
+    }
+}
+
+// What running a statement for part 2 did, to audit why a mul was or was not counted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Traced {
+    pub statement: Statement,
+    // Whether multiplications were enabled when the statement ran
+    pub enabled: bool,
+    // How much it added to the total
+    pub contribution: i32,
+}
+
+impl fmt::Display for Traced {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Statement { instruction, span } = &self.statement;
+        write!(f, "{}..{} {instruction}", span.start, span.end)?;
+        match instruction {
+            Instruction::Mul(..) if self.enabled => write!(f, " +{}", self.contribution),
+            Instruction::Mul(..) => write!(f, " skipped, disabled"),
+            _ => Ok(()),
+        }
+    }
+}
+
+pub fn trace(program: &[Statement]) -> Vec<Traced> {
+    let mut state = Conditional::default();
+    program
+        .iter()
+        .map(|statement| {
+            let (enabled, before) = (state.enabled, state.total);
+            state.execute(statement.instruction);
+            Traced {
+                statement: statement.clone(),
+                enabled,
+                contribution: state.total - before,
+            }
+        })
+        .collect()
+}
+
+// The input for a terminal, with counted muls in green, skipped ones struck through in red, do
+// and don't in cyan and yellow, and all the garbage in between dimmed
+pub fn render_ansi(input: &str, trace: &[Traced]) -> String {
+    render(
+        input,
+        trace,
+        |text| format!("\x1b[2m{text}\x1b[0m"),
+        |text, traced| {
+            let style = match traced.statement.instruction {
+                Instruction::Mul(..) if traced.enabled => "1;32",
+                Instruction::Mul(..) => "9;31",
+                Instruction::Do => "36",
+                Instruction::Dont => "33",
+            };
+            format!("\x1b[{style}m{text}\x1b[0m")
+        },
+    )
+}
+
+// The input as a standalone HTML page, each instruction saying what it did when hovered over
+pub fn render_html(input: &str, trace: &[Traced]) -> String {
+    let body = render(input, trace, escape_html, |text, traced| {
+        let class = match traced.statement.instruction {
+            Instruction::Mul(..) if traced.enabled => "enabled",
+            Instruction::Mul(..) => "disabled",
+            Instruction::Do => "do",
+            Instruction::Dont => "dont",
+        };
+        format!(
+            "<span class=\"{class}\" title=\"{}\">{}</span>",
+            escape_html(&traced.to_string()),
+            escape_html(text)
+        )
+    });
+
+    format!(
+        "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>Day 3 trace</title>
+<style>
+pre {{ color: #999; white-space: pre-wrap; word-break: break-all; }}
+.enabled {{ color: #080; font-weight: bold; }}
+.disabled {{ color: #c00; text-decoration: line-through; }}
+.do {{ color: #088; }}
+.dont {{ color: #a60; }}
+</style>
+</head>
+<body>
+<pre>{body}</pre>
+</body>
+</html>
+"
+    )
+}
+
+// Walks the input, styling the traced spans with instruction and everything else with garbage
+fn render(
+    input: &str,
+    trace: &[Traced],
+    garbage: impl Fn(&str) -> String,
+    instruction: impl Fn(&str, &Traced) -> String,
+) -> String {
+    let mut out = String::with_capacity(input.len() * 2);
+    let mut pos = 0;
+    for traced in trace {
+        let span = traced.statement.span.clone();
+        if span.start > pos {
+            out += &garbage(&input[pos..span.start]);
+        }
+        out += &instruction(&input[span.clone()], traced);
+        pos = span.end;
+    }
+    if pos < input.len() {
+        out += &garbage(&input[pos..]);
+    }
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped += "&amp;",
+            '<' => escaped += "&lt;",
+            '>' => escaped += "&gt;",
+            '"' => escaped += "&quot;",
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// Where the scanner is in an instruction, named after the bytes read so far
+#[derive(Debug, Clone, Copy)]
+enum Scan {
+    Start,
+    M,
+    Mu,
+    Mul,
+    // Number of digits of the first or second argument so far
+    First(usize),
+    Second(usize),
+    D,
+    Do,
+    DoOpen,
+    Don,
+    DonQuote,
+    DonT,
+    DontOpen,
+}
+
+// A byte at a time state machine running instructions on state as soon as it has read them,
+// without going through tokens or regex
+pub fn scan<S: State>(input: &str, mut state: S) -> S {
+    let (mut a, mut b) = (0, 0);
+    let mut scan = Scan::Start;
+
+    for &byte in input.as_bytes() {
+        let digit = (byte as i32).wrapping_sub(b'0' as i32);
+        scan = match (scan, byte) {
+            (Scan::M, b'u') => Scan::Mu,
+            (Scan::Mu, b'l') => Scan::Mul,
+            (Scan::Mul, b'(') => {
+                a = 0;
+                Scan::First(0)
+            }
+            (Scan::First(n), b'0'..=b'9') if n < Instruction::MAX_DIGITS => {
+                a = a * 10 + digit;
+                Scan::First(n + 1)
+            }
+            (Scan::First(n), b',') if n > 0 => {
+                b = 0;
+                Scan::Second(0)
+            }
+            (Scan::Second(n), b'0'..=b'9') if n < Instruction::MAX_DIGITS => {
+                b = b * 10 + digit;
+                Scan::Second(n + 1)
+            }
+            (Scan::Second(n), b')') if n > 0 => {
+                state.execute(Instruction::Mul(a, b));
+                Scan::Start
+            }
+            (Scan::D, b'o') => Scan::Do,
+            (Scan::Do, b'(') => Scan::DoOpen,
+            (Scan::DoOpen, b')') => {
+                state.execute(Instruction::Do);
+                Scan::Start
+            }
+            (Scan::Do, b'n') => Scan::Don,
+            (Scan::Don, b'\'') => Scan::DonQuote,
+            (Scan::DonQuote, b't') => Scan::DonT,
+            (Scan::DonT, b'(') => Scan::DontOpen,
+            (Scan::DontOpen, b')') => {
+                state.execute(Instruction::Dont);
+                Scan::Start
+            }
+            // Anything else starts over, but the byte might begin the next instruction. None
+            // of them have an m or d past their first byte, so there is nothing else to retry.
+            (_, b'm') => Scan::M,
+            (_, b'd') => Scan::D,
+            _ => Scan::Start,
+        };
+    }
+
+    state
+}
+
+#[cfg(feature = "regex")]
+pub fn part1(input: &str) -> i32 {
+    static MATCHER: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"mul\((\d{1,3}),(\d{1,3})\)").unwrap());
+
+    MATCHER
+        .captures_iter(input)
+        .map(|cap| {
+            let a = parse::integer::<i32>(&cap[1]).unwrap();
+            let b = parse::integer::<i32>(&cap[2]).unwrap();
+            a * b
+        })
+        .sum()
+}
+
+#[cfg(feature = "regex")]
+pub fn part2(input: &str) -> i32 {
+    static MATCHER: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"(?<do>do\(\))|(?<mul>mul\((?<a>\d{1,3}),(?<b>\d{1,3})\))|(?<dont>don't\(\))")
+            .unwrap()
+    });
+
+    let mut do_capture = true;
+    MATCHER
+        .captures_iter(input)
+        .map(|cap| {
+            if cap.name("mul").is_some() {
+                if do_capture {
+                    let a = parse::integer::<i32>(&cap["a"]).unwrap();
+                    let b = parse::integer::<i32>(&cap["b"]).unwrap();
+                    return a * b;
+                } else {
+                    return 0;
+                }
+            } else if cap.name("do").is_some() {
+                do_capture = true
+            } else if cap.name("dont").is_some() {
+                do_capture = false
+            }
+            0
+        })
+        .sum()
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    util::answer_tests! {
+        Day3,
+        sample_part1: "input_sample_part1.txt" => [1: 161],
+        sample_part2: "input_sample_part2.txt" => [2: 48],
+        input: "input.txt" => [1: 175615763, 2: 74361272],
+    }
+
+    #[test]
+    fn test_program() {
+        let input = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+        let program = parse_program(input);
+        let instructions = program.iter().map(|s| s.instruction).collect::<Vec<_>>();
+        assert_eq!(
+            instructions,
+            [
+                Instruction::Mul(2, 4),
+                Instruction::Dont,
+                Instruction::Mul(5, 5),
+                Instruction::Mul(11, 8),
+                Instruction::Do,
+                Instruction::Mul(8, 5),
+            ]
+        );
+        assert_eq!(&input[program[1].span.clone()], "don't()");
+        assert_eq!(&input[program[4].span.clone()], "do()");
+
+        let corrupted = [
+            "mul(1234,5)",
+            "mul(1,2,3)",
+            "mul ( 1,2)",
+            "mul()",
+            "do(1)",
+            "mul(-1,2)",
+        ];
+        for corrupted in corrupted {
+            assert_eq!(parse_program(corrupted), [], "{corrupted}");
+        }
+        let program = parse_program("mul(003,1)mul(999,2)mul(1,2");
+        assert_eq!(run(&program, Accumulator::default()).total, 2001);
+    }
+
+    #[test]
+    fn test_trace() {
+        let input = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+        let trace = trace(&parse_program(input));
+        let contributions = trace
+            .iter()
+            .map(|t| (t.enabled, t.contribution))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            contributions,
+            [
+                (true, 8),
+                (true, 0),
+                (false, 0),
+                (false, 0),
+                (false, 0),
+                (true, 40)
+            ]
+        );
+        assert_eq!(trace[0].to_string(), "1..9 mul(2,4) +8");
+        assert_eq!(trace[2].to_string(), "28..36 mul(5,5) skipped, disabled");
+
+        let html = render_html(input, &trace);
+        assert!(html.contains(
+            "<pre>x<span class=\"enabled\" title=\"1..9 mul(2,4) +8\">mul(2,4)</span>&amp;"
+        ));
+        assert!(html.contains("<span class=\"dont\" title=\"20..27 don't()\">don't()</span>"));
+        assert!(html.contains("<span class=\"disabled\""));
+
+        let ansi = render_ansi(input, &trace);
+        assert!(ansi.starts_with("\x1b[2mx\x1b[0m\x1b[1;32mmul(2,4)\x1b[0m"));
+        assert!(ansi.contains("\x1b[9;31mmul(5,5)\x1b[0m"));
+    }
+
+    #[test]
+    fn test_scanner() {
+        // Random mixes of instructions, near misses and garbage
+        let fragments = [
+            "mul(",
+            "mul",
+            "do()",
+            "don't()",
+            "do(",
+            "don't",
+            "(",
+            ")",
+            ",",
+            "12",
+            "3",
+            "1234",
+            "m",
+            "d",
+            "x",
+            " ",
+            "'",
+            "mmul(2,3)",
+            "dodon't()",
+        ];
+        let mut rng = util::synthetic::Rng::new(3);
+        for _ in 0..500 {
+            let input = (0..40).map(|_| *rng.choose(&fragments)).collect::<String>();
+            let program = parse_program(&input);
+            assert_eq!(
+                scan(&input, Accumulator::default()).total,
+                run(&program, Accumulator::default()).total,
+                "{input}"
+            );
+            assert_eq!(
+                scan(&input, Conditional::default()).total,
+                run(&program, Conditional::default()).total,
+                "{input}"
+            );
+        }
+    }
+}
@@ -0,0 +1,507 @@
+use std::{ops::Range, sync::LazyLock};
+use util::{
+    geom::{Direction, Point},
+    stencil::Stencil,
+    synthetic::Rng,
+    wordsearch::{WordSearch, Wrap},
+    Answer, Grid, ParseError, Solution, Variant,
+};
+
+pub type ByteArray2D = Grid<u8>;
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: u8 = 4;
+    const DIR: &'static str = env!("CARGO_MANIFEST_DIR");
+
+    type Input = ByteArray2D;
+
+    fn parse(input: &str) -> Result<ByteArray2D, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(haystack: &ByteArray2D) -> Answer {
+        part1_naive_array_search(haystack).into()
+    }
+
+    fn part2(haystack: &ByteArray2D) -> Answer {
+        part2(haystack).into()
+    }
+
+    fn variants() -> Vec<Variant<ByteArray2D>> {
+        #[allow(unused_mut)]
+        let mut variants = vec![
+            Variant::new(
+                1,
+                "naive",
+                "Part 1 (naive array search)",
+                part1_naive_array_search,
+            ),
+            Variant::new(
+                1,
+                "column_first",
+                "Part 1 (naive array search, but iterate columns instead of rows)",
+                part1_naive_array_search_column_first,
+            ),
+            Variant::new(
+                1,
+                "reduced",
+                "Part 1 (naive array search reduced)",
+                part1_naive_array_search_reduced,
+            ),
+            Variant::new(
+                1,
+                "extract_string",
+                "Part 1 (naive array extract_string)",
+                part1_naive_extract_string,
+            ),
+            Variant::new(
+                1,
+                "extract_string_reduced",
+                "Part 1 (naive array extract_string reduced)",
+                part1_naive_extract_string_reduced,
+            ),
+            Variant::new(
+                1,
+                "aho_corasick",
+                "Part 1 (word search, Aho-Corasick over every line)",
+                part1_word_search,
+            ),
+            Variant::new(
+                1,
+                "bit_parallel",
+                "Part 1 (bit-parallel, 64 cells per word)",
+                part1_bit_parallel,
+            ),
+            Variant::new(2, "naive", "Part 2 (naive search)", part2),
+            Variant::new(
+                2,
+                "stencil",
+                "Part 2 (stencil, every rotation and reflection)",
+                part2_stencil,
+            ),
+        ];
+
+        #[cfg(feature = "parallel")]
+        variants.extend([
+            Variant::new(
+                1,
+                "parallel",
+                "Part 1 (naive array search, parallel row bands)",
+                part1_parallel,
+            ),
+            Variant::new(
+                2,
+                "parallel",
+                "Part 2 (naive search, parallel row bands)",
+                part2_parallel,
+            ),
+        ]);
+
+        variants
+    }
+}
+
+pub fn parse_input(input: &str) -> Result<ByteArray2D, ParseError> {
+    // Word searches have no spaces, so whitespace at the end of a row is only noise
+    let trailing = |l: &str| l.ends_with(|c: char| c.is_ascii_whitespace());
+    if !input.trim_end().lines().any(trailing) {
+        return Grid::parse_bytes(input.trim_end());
+    }
+
+    let trimmed = input
+        .trim_end()
+        .lines()
+        .map(str::trim_ascii_end)
+        .collect::<Vec<_>>()
+        .join("\n");
+    Grid::parse_bytes(&trimmed)
+}
+
+// A size by size grid of random XMAS letters, for benchmarking with AOC_SYNTHETIC
+pub fn synthetic_input(size: usize, seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let mut input = String::with_capacity(size * (size + 1));
+    for _ in 0..size {
+        input.extend((0..size).map(|_| *rng.choose(&['X', 'M', 'A', 'S'])));
+        input.push('\n');
+    }
+    input
+}
+
+pub fn part1_naive_array_search(haystack: &ByteArray2D) -> usize {
+    count_xmas(haystack, 0..haystack.height)
+}
+
+// Matches starting in rows, which can still reach into the rows around them
+fn count_xmas(haystack: &ByteArray2D, rows: Range<usize>) -> usize {
+    let mut count = 0;
+    let needle = b"XMAS";
+
+    // Iterate through each line and search in all eight directions
+    for y in rows {
+        for x in 0..haystack.width {
+            if haystack[(x, y)] == needle[0] {
+                count += Direction::iter_all()
+                    .filter(|dir| match_bytes_direction(haystack, needle, x, y, *dir))
+                    .count();
+            }
+        }
+    }
+    count
+}
+
+pub fn part1_naive_array_search_column_first(haystack: &ByteArray2D) -> usize {
+    let mut count = 0;
+    let needle = b"XMAS";
+
+    // Iterate through each line and search in all eight directions
+    for x in 0..haystack.width {
+        for y in 0..haystack.height {
+            if haystack[(x, y)] == needle[0] {
+                count += Direction::iter_all()
+                    .filter(|dir| match_bytes_direction(haystack, needle, x, y, *dir))
+                    .count();
+            }
+        }
+    }
+    count
+}
+
+pub fn part1_naive_array_search_reduced(haystack: &ByteArray2D) -> usize {
+    let mut count = 0;
+
+    let needle = b"XMAS";
+    let needle_reversed = b"SAMX";
+
+    // Iterate through each line and search in all eight directions
+    for y in 0..haystack.height {
+        for x in 0..haystack.width {
+            let start = haystack[(x, y)];
+            if start == needle[0] {
+                count += Direction::iter_reduced()
+                    .filter(|dir| match_bytes_direction(haystack, needle, x, y, *dir))
+                    .count();
+            }
+
+            if start == needle_reversed[0] {
+                count += Direction::iter_reduced()
+                    .filter(|dir| match_bytes_direction(haystack, needle_reversed, x, y, *dir))
+                    .count();
+            }
+        }
+    }
+
+    count
+}
+
+pub fn part1_naive_extract_string(haystack: &ByteArray2D) -> usize {
+    let mut count = 0;
+
+    const NEEDLE: &[u8; 4] = b"XMAS";
+
+    for y in 0..haystack.height {
+        for x in 0..haystack.width {
+            if haystack[(x, y)] == NEEDLE[0] {
+                count += Direction::iter_all()
+                    .filter(|dir| {
+                        if dir.ray_fits(NEEDLE.len(), x, y, haystack.width, haystack.height) {
+                            let extracted =
+                                extract_string::<{ NEEDLE.len() }>(haystack, x, y, *dir);
+                            &extracted == NEEDLE
+                        } else {
+                            false
+                        }
+                    })
+                    .count();
+            }
+        }
+    }
+
+    count
+}
+
+pub fn part1_naive_extract_string_reduced(haystack: &ByteArray2D) -> usize {
+    let mut count = 0;
+
+    const NEEDLE: &[u8; 4] = b"XMAS";
+    const NEEDLE_REVERSED: &[u8; 4] = b"SAMX";
+
+    // Iterate through each line and search in all eight directions
+    for y in 0..haystack.height {
+        for x in 0..haystack.width {
+            if haystack[(x, y)] == NEEDLE[0] || haystack[(x, y)] == NEEDLE_REVERSED[0] {
+                count += Direction::iter_reduced()
+                    .filter(|dir| {
+                        if dir.ray_fits(NEEDLE.len(), x, y, haystack.width, haystack.height) {
+                            let extracted =
+                                extract_string::<{ NEEDLE.len() }>(haystack, x, y, *dir);
+                            &extracted == NEEDLE || &extracted == NEEDLE_REVERSED
+                        } else {
+                            false
+                        }
+                    })
+                    .count();
+            }
+        }
+    }
+
+    count
+}
+
+pub fn part1_word_search(haystack: &ByteArray2D) -> usize {
+    static SEARCH: LazyLock<WordSearch> =
+        LazyLock::new(|| WordSearch::new(&[b"XMAS"], &Direction::ALL, Wrap::Bounded));
+
+    SEARCH.count(haystack)
+}
+
+pub fn part1_bit_parallel(haystack: &ByteArray2D) -> usize {
+    count_bit_parallel(haystack, b"XMAS")
+}
+
+// Keeps one bit per cell for each needle byte, then for each direction ANDs a row of the first
+// byte with the rows of the later bytes shifted along that direction, 64 cells at a time
+fn count_bit_parallel(haystack: &ByteArray2D, needle: &[u8]) -> usize {
+    let words = haystack.width.div_ceil(64);
+    let planes = needle
+        .iter()
+        .map(|&b| bit_plane(haystack, b, words))
+        .collect::<Vec<_>>();
+    let row = |k: usize, y: usize| &planes[k][y * words..(y + 1) * words];
+
+    let height = haystack.height as isize;
+    let reach = needle.len() as isize - 1;
+    let mut count = 0;
+    for direction in Direction::iter_all() {
+        let step = direction.delta();
+        for y in 0..height {
+            if !(0..height).contains(&(y + reach * step.dy)) {
+                continue;
+            }
+
+            for w in 0..words {
+                let mut found = row(0, y as usize)[w];
+                for k in 1..needle.len() {
+                    if found == 0 {
+                        break;
+                    }
+                    let offset = step * k as isize;
+                    let shifted = shifted_word(row(k, (y + offset.dy) as usize), w, offset.dx);
+                    found &= shifted;
+                }
+                count += found.count_ones() as usize;
+            }
+        }
+    }
+
+    count
+}
+
+// Bit x of the result is set when haystack has byte at x on that row
+fn bit_plane(haystack: &ByteArray2D, byte: u8, words: usize) -> Vec<u64> {
+    const LOW_BITS: u64 = 0x7f7f_7f7f_7f7f_7f7f;
+    const HIGH_BITS: u64 = 0x8080_8080_8080_8080;
+    let pattern = u64::from_le_bytes([byte; 8]);
+
+    let mut plane = vec![0u64; haystack.height * words];
+    for (y, cells) in haystack.rows().enumerate() {
+        let bits = &mut plane[y * words..(y + 1) * words];
+
+        // Compare 8 cells at once, the high bit of each byte is set where it was equal to byte
+        let mut chunks = cells.chunks_exact(8);
+        for (i, chunk) in chunks.by_ref().enumerate() {
+            let diff = u64::from_le_bytes(chunk.try_into().unwrap()) ^ pattern;
+            let equal = !(((diff & LOW_BITS) + LOW_BITS) | diff) & HIGH_BITS;
+            // Gather the high bits into the top byte
+            let mask = (equal >> 7).wrapping_mul(0x0102_0408_1020_4080) >> 56;
+            bits[i / 8] |= mask << (i % 8 * 8);
+        }
+
+        let done = cells.len() - chunks.remainder().len();
+        for (x, _) in chunks
+            .remainder()
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| **b == byte)
+        {
+            bits[(done + x) / 64] |= 1 << ((done + x) % 64);
+        }
+    }
+    plane
+}
+
+// Word w of the row shifted so bit x holds what was at bit x + shift, with zeros shifted in
+fn shifted_word(row: &[u64], w: usize, shift: isize) -> u64 {
+    let start = w as isize * 64 + shift;
+    let (index, bit) = (start.div_euclid(64), start.rem_euclid(64) as u32);
+    let word = |i: isize| usize::try_from(i).ok().and_then(|i| row.get(i)).copied();
+
+    let low = word(index).unwrap_or(0) >> bit;
+    let high = match bit {
+        0 => 0,
+        _ => word(index + 1).unwrap_or(0) << (64 - bit),
+    };
+    low | high
+}
+
+pub fn part2(haystack: &ByteArray2D) -> usize {
+    count_x_mas(haystack, 1..haystack.height - 1)
+}
+
+fn count_x_mas(haystack: &ByteArray2D, rows: Range<usize>) -> usize {
+    let mut count = 0;
+
+    // We can skip the first and last columns
+    for y in rows {
+        for x in 1..haystack.width - 1 {
+            if haystack[(x, y)] == b'A' {
+                let tl = haystack[(x - 1, y - 1)];
+                let tr = haystack[(x + 1, y - 1)];
+                let bl = haystack[(x - 1, y + 1)];
+                let br = haystack[(x + 1, y + 1)];
+
+                let tl_br_match = (tl == b'S' && br == b'M') || (tl == b'M' && br == b'S');
+                let tr_bl_match = (tr == b'S' && bl == b'M') || (tr == b'M' && bl == b'S');
+
+                if tl_br_match && tr_bl_match {
+                    count += 1;
+                }
+            }
+        }
+    }
+    count
+}
+
+// Splits the rows into one band per thread, matches can still reach into the neighbouring bands
+#[cfg(feature = "parallel")]
+pub fn part1_parallel(haystack: &ByteArray2D) -> usize {
+    util::parallel::map_ranges(haystack.height, |rows| count_xmas(haystack, rows))
+        .into_iter()
+        .sum()
+}
+
+#[cfg(feature = "parallel")]
+pub fn part2_parallel(haystack: &ByteArray2D) -> usize {
+    // Bands of the rows between the first and last one
+    util::parallel::map_ranges(haystack.height.saturating_sub(2), |rows| {
+        count_x_mas(haystack, rows.start + 1..rows.end + 1)
+    })
+    .into_iter()
+    .sum()
+}
+
+pub fn part2_stencil(haystack: &ByteArray2D) -> usize {
+    static X_MAS: LazyLock<Stencil> =
+        LazyLock::new(|| Stencil::parse("M.S\n.A.\nM.S", '.').unwrap());
+
+    X_MAS.count(haystack)
+}
+
+fn extract_string<const N: usize>(
+    haystack: &ByteArray2D,
+    x: usize,
+    y: usize,
+    direction: Direction,
+) -> [u8; N] {
+    let mut result = [0u8; N];
+
+    let (start, step) = (Point::from_index((x, y)), direction.delta());
+    (0..N).for_each(|i| result[i] = haystack[start + step * i as isize]);
+
+    result
+}
+
+fn match_bytes_direction(
+    haystack: &ByteArray2D,
+    needle: &[u8],
+    x: usize,
+    y: usize,
+    direction: Direction,
+) -> bool {
+    if !direction.ray_fits(needle.len(), x, y, haystack.width, haystack.height) {
+        false
+    } else {
+        let (start, step) = (Point::from_index((x, y)), direction.delta());
+        needle
+            .iter()
+            .enumerate()
+            .all(|(i, b)| haystack[start + step * i as isize] == *b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    util::answer_tests! {
+        Day4,
+        sample: "input_sample_part1.txt" => [1: 18, 2: 9],
+        input: "input.txt" => [1: 2397, 2: 1824],
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = parse_input("XMAS  \r\nSAMX\t\n\n").unwrap();
+        assert_eq!((grid.width, grid.height), (4, 2));
+        assert_eq!(grid.entries, b"XMASSAMX");
+
+        let e = parse_input("XMAS \nSAM \n").unwrap_err();
+        assert_eq!((e.line, e.column), (2, 1));
+    }
+
+    #[test]
+    fn test_bit_parallel() {
+        // Widths around the 8 byte chunks and 64 bit words
+        for size in [1, 7, 8, 9, 63, 64, 65, 130] {
+            let haystack = parse_input(&synthetic_input(size, size as u64)).unwrap();
+            assert_eq!(
+                part1_bit_parallel(&haystack),
+                part1_naive_array_search(&haystack),
+                "size {size}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_matcher() {
+        let input = "S..S..S\n\
+                           .A.A.A.\n\
+                           ..MMM..\n\
+                           SAMXMAS\n\
+                           ..MMM..\n\
+                           .A.A.A.\n\
+                           S..S..S\n";
+
+        let haystack = parse_input(input).unwrap();
+        let width = haystack.width;
+        let height = haystack.height;
+
+        let needle = b"XMAS";
+        let needle_reversed = b"SAMX";
+        Direction::iter_all().for_each(|dir| {
+            assert!(match_bytes_direction(&haystack, needle, 3, 3, dir));
+        });
+
+        let mut count_all = 0;
+        let mut count_reduced = 0;
+        for i in 0..width * height {
+            let x = i % width;
+            let y = i / width;
+
+            count_all += Direction::iter_all()
+                .filter(|dir| match_bytes_direction(&haystack, needle, x, y, *dir))
+                .count();
+
+            count_reduced += Direction::iter_reduced()
+                .filter(|dir| match_bytes_direction(&haystack, needle, x, y, *dir))
+                .count();
+            count_reduced += Direction::iter_reduced()
+                .filter(|dir| match_bytes_direction(&haystack, needle_reversed, x, y, *dir))
+                .count();
+        }
+
+        assert_eq!(count_all, count_reduced);
+    }
+}
@@ -0,0 +1,209 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use util::{parse::Cursor, Answer, ParseError, Solution, Variant};
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    const DAY: u8 = 5;
+    const DIR: &'static str = env!("CARGO_MANIFEST_DIR");
+
+    type Input = (RuleSet, Vec<Vec<i32>>);
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1((ruleset, page_lists): &Self::Input) -> Answer {
+        part1(ruleset, page_lists).into()
+    }
+
+    // NOTE: Measurement here will be a little off because of the clone we have to do because the function is not idempotent
+    fn part2((ruleset, page_lists): &Self::Input) -> Answer {
+        part2(ruleset, &mut page_lists.clone()).into()
+    }
+
+    fn variants() -> Vec<Variant<Self::Input>> {
+        #[allow(unused_mut)]
+        let mut variants = vec![
+            Variant::new(1, "default", "Part 1", Self::part1),
+            Variant::new(
+                2,
+                "sort",
+                "Part 2 (with cloning and sorting, not a good measurement)",
+                Self::part2,
+            ),
+        ];
+
+        #[cfg(feature = "parallel")]
+        variants.extend([
+            Variant::new(
+                1,
+                "parallel",
+                "Part 1 (parallel)",
+                |(ruleset, page_lists): &Self::Input| part1_parallel(ruleset, page_lists),
+            ),
+            Variant::new(
+                2,
+                "parallel",
+                "Part 2 (with cloning and sorting, parallel)",
+                |(ruleset, page_lists): &Self::Input| {
+                    part2_parallel(ruleset, &mut page_lists.clone())
+                },
+            ),
+        ]);
+
+        variants
+    }
+}
+
+pub type RuleSet = HashMap<i32, Vec<i32>>;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ComparisonResult {
+    Equal,
+    LessThan,
+    GreaterThan,
+    Unknown,
+}
+
+impl From<ComparisonResult> for Ordering {
+    fn from(result: ComparisonResult) -> Self {
+        match result {
+            ComparisonResult::Equal => Ordering::Equal,
+            ComparisonResult::LessThan => Ordering::Less,
+            ComparisonResult::GreaterThan => Ordering::Greater,
+            ComparisonResult::Unknown => panic!("Unresolved ordering!"),
+        }
+    }
+}
+
+pub fn part1(ruleset: &RuleSet, page_lists: &[Vec<i32>]) -> i32 {
+    page_lists
+        .iter()
+        .map(|page_list| {
+            if part1_pages_ordered(page_list, ruleset) {
+                page_list[page_list.len() / 2]
+            } else {
+                0
+            }
+        })
+        .sum::<i32>()
+}
+
+// NOTE: Sorts the unordered page lists in place
+pub fn part2(ruleset: &RuleSet, page_lists: &mut [Vec<i32>]) -> i32 {
+    page_lists
+        .iter_mut()
+        .map(|page_list| {
+            if !part1_pages_ordered(page_list, ruleset) {
+                page_list.sort_by(|a, b| cmp_pages(*a, *b, ruleset).into());
+                page_list[page_list.len() / 2]
+            } else {
+                0
+            }
+        })
+        .sum::<i32>()
+}
+
+// Splits the page lists into one chunk per thread
+#[cfg(feature = "parallel")]
+pub fn part1_parallel(ruleset: &RuleSet, page_lists: &[Vec<i32>]) -> i32 {
+    util::parallel::map_chunks(page_lists, |chunk| part1(ruleset, chunk))
+        .into_iter()
+        .sum()
+}
+
+// NOTE: Sorts the unordered page lists in place, like part2
+#[cfg(feature = "parallel")]
+pub fn part2_parallel(ruleset: &RuleSet, page_lists: &mut [Vec<i32>]) -> i32 {
+    util::parallel::map_chunks_mut(page_lists, |chunk| part2(ruleset, chunk))
+        .into_iter()
+        .sum()
+}
+
+pub fn part1_pages_ordered(page_list: &[i32], ruleset: &RuleSet) -> bool {
+    for i in 1..page_list.len() {
+        let (a, b) = (page_list[i - 1], page_list[i]);
+        match cmp_pages(a, b, ruleset) {
+            ComparisonResult::Equal | ComparisonResult::GreaterThan => return false,
+            ComparisonResult::Unknown => panic!("Undecided entry found: {a} <=> {b}"),
+            ComparisonResult::LessThan => (),
+        }
+    }
+
+    true
+}
+
+pub fn cmp_pages(a: i32, b: i32, ruleset: &RuleSet) -> ComparisonResult {
+    // Try to find a path from a -> b
+    if a == b {
+        return ComparisonResult::Equal;
+    }
+
+    if let Some(a_less_than) = ruleset.get(&a) {
+        if a_less_than.contains(&b) {
+            return ComparisonResult::LessThan;
+        }
+    }
+
+    if let Some(b_less_than) = ruleset.get(&b) {
+        if b_less_than.contains(&a) {
+            return ComparisonResult::GreaterThan;
+        }
+    }
+
+    ComparisonResult::Unknown
+}
+
+pub fn parse_input(input: &str) -> Result<(RuleSet, Vec<Vec<i32>>), ParseError> {
+    let mut ruleset = RuleSet::new();
+    let mut page_list = vec![];
+
+    let mut cursor = Cursor::new(input);
+
+    // Rules up to the first empty line
+    while !cursor.eat_line_end() {
+        let val = cursor.integer()?;
+        cursor.expect(b'|', "expected a rule like 47|53")?;
+        let less_than = cursor.integer()?;
+        if !cursor.eat_line_end() {
+            return Err(cursor.error_here("expected a rule like 47|53"));
+        }
+
+        if let Some(val_less_than) = ruleset.get_mut(&val) {
+            val_less_than.push(less_than);
+        } else {
+            ruleset.insert(val, vec![less_than]);
+        }
+    }
+
+    // Then one comma separated list of pages per line
+    while !cursor.is_at_end() {
+        if cursor.eat_line_end() {
+            continue;
+        }
+
+        let mut pages = vec![cursor.integer()?];
+        while cursor.eat(b',') {
+            pages.push(cursor.integer()?);
+        }
+        if !cursor.eat_line_end() {
+            return Err(cursor.error_here("expected a comma between page numbers"));
+        }
+        page_list.push(pages);
+    }
+
+    Ok((ruleset, page_list))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    util::answer_tests! {
+        Day5,
+        sample: "input_sample_part1.txt" => [1: 143, 2: 123],
+        input: "input.txt" => [1: 6041, 2: 4884],
+    }
+}
@@ -0,0 +1,291 @@
+use std::{
+    fmt, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::measure::{Comparison, Summary};
+
+// Changes smaller than this are treated as noise even if they are statistically significant
+const NOISE_THRESHOLD: f64 = 0.05;
+
+const HEADER: &str = "group,label,iterations,mean_ns,std_dev_ns,recorded_at";
+
+static GROUP: Mutex<Option<String>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    // Compare against the stored baseline and replace it with the new result
+    Update,
+    // Compare only, leave the stored baseline untouched
+    Compare,
+    Off,
+}
+
+impl Mode {
+    // AOC_BASELINE_MODE is one of update (default), compare or off
+    pub fn from_env() -> Self {
+        match std::env::var("AOC_BASELINE_MODE").as_deref() {
+            Ok("compare") => Mode::Compare,
+            Ok("off") => Mode::Off,
+            _ => Mode::Update,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Improved,
+    Regressed,
+    Unchanged,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BaselineComparison {
+    pub baseline: Summary,
+    pub comparison: Comparison,
+}
+
+impl BaselineComparison {
+    pub fn verdict(&self) -> Verdict {
+        let c = &self.comparison;
+        if !c.significant || c.change.abs() < NOISE_THRESHOLD {
+            Verdict::Unchanged
+        } else if c.change < 0.0 {
+            Verdict::Improved
+        } else {
+            Verdict::Regressed
+        }
+    }
+}
+
+impl fmt::Display for BaselineComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verdict = match self.verdict() {
+            Verdict::Improved => "improved",
+            Verdict::Regressed => "REGRESSED",
+            Verdict::Unchanged => "unchanged",
+        };
+        write!(
+            f,
+            "Baseline: {:?} -> {}: {:+.2}% (t = {:.2})",
+            self.baseline.mean,
+            verdict,
+            self.comparison.change * 100.0,
+            self.comparison.t
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub group: String,
+    pub label: String,
+    pub summary: Summary,
+    pub recorded_at: u64,
+}
+
+// Groups key the baselines, defaulting to the name of the running binary (e.g. day4)
+pub fn set_group(group: &str) {
+    *GROUP.lock().unwrap() = Some(group.to_string());
+}
+
+pub fn current_group() -> String {
+    if let Some(group) = GROUP.lock().unwrap().as_ref() {
+        return group.clone();
+    }
+
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.file_stem().map(|s| s.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+// AOC_BASELINE overrides the location of the baseline file
+pub fn default_path() -> PathBuf {
+    match std::env::var_os("AOC_BASELINE") {
+        Some(path) => PathBuf::from(path),
+        None => Path::new(env!("CARGO_MANIFEST_DIR")).join("../target/aoc-baseline.csv"),
+    }
+}
+
+// Compares a fresh summary with its stored baseline, saving it depending on the mode
+pub fn record(label: &str, summary: &Summary) -> Option<BaselineComparison> {
+    let mode = Mode::from_env();
+    if mode == Mode::Off {
+        return None;
+    }
+
+    let path = default_path();
+    let group = current_group();
+
+    let mut records = match load(&path) {
+        Ok(records) => records,
+        Err(e) => {
+            eprintln!("Unable to read baseline {}: {}", path.display(), e);
+            return None;
+        }
+    };
+
+    let existing = records
+        .iter_mut()
+        .find(|r| r.group == group && r.label == label);
+
+    let comparison = existing.as_ref().map(|r| BaselineComparison {
+        baseline: r.summary,
+        comparison: summary.compare(&r.summary),
+    });
+
+    if mode == Mode::Update {
+        let recorded_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        match existing {
+            Some(r) => {
+                r.summary = *summary;
+                r.recorded_at = recorded_at;
+            }
+            None => records.push(Record {
+                group,
+                label: label.to_string(),
+                summary: *summary,
+                recorded_at,
+            }),
+        }
+
+        if let Err(e) = save(&path, &records) {
+            eprintln!("Unable to write baseline {}: {}", path.display(), e);
+        }
+    }
+
+    comparison
+}
+
+pub fn load(path: &Path) -> io::Result<Vec<Record>> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e),
+    };
+
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(i, line)| !(line.is_empty() || *i == 0 && *line == HEADER))
+        .map(|(i, line)| {
+            parse_record(line).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("malformed record on line {}: {}", i + 1, line),
+                )
+            })
+        })
+        .collect()
+}
+
+pub fn save(path: &Path, records: &[Record]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut out = io::BufWriter::new(fs::File::create(path)?);
+    writeln!(out, "{}", HEADER)?;
+    for r in records {
+        writeln!(
+            out,
+            "{},{},{},{},{},{}",
+            quote(&r.group),
+            quote(&r.label),
+            r.summary.iterations,
+            r.summary.mean.as_nanos(),
+            r.summary.std_dev.as_nanos(),
+            r.recorded_at
+        )?;
+    }
+    out.flush()
+}
+
+fn quote(field: &str) -> String {
+    format!("\"{}\"", field.replace('"', "\"\""))
+}
+
+// Reads a leading quoted field, returning it and the rest of the line after the comma
+fn unquote(line: &str) -> Option<(String, &str)> {
+    let mut field = String::new();
+    let mut chars = line.strip_prefix('"')?.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' if matches!(chars.peek(), Some((_, '"'))) => {
+                chars.next();
+                field.push('"');
+            }
+            '"' => return Some((field, line[i + 2..].strip_prefix(',')?)),
+            c => field.push(c),
+        }
+    }
+
+    None
+}
+
+fn parse_record(line: &str) -> Option<Record> {
+    let (group, rest) = unquote(line)?;
+    let (label, rest) = unquote(rest)?;
+
+    let mut numbers = rest.split(',').map(|n| n.parse::<u64>().ok());
+    let mut next = || numbers.next().flatten();
+    let record = Record {
+        group,
+        label,
+        summary: Summary {
+            iterations: next()? as usize,
+            mean: Duration::from_nanos(next()?),
+            std_dev: Duration::from_nanos(next()?),
+        },
+        recorded_at: next()?,
+    };
+
+    numbers.next().is_none().then_some(record)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let records = vec![
+            Record {
+                group: "day4".to_string(),
+                label: "Part 1 (naive array search, but iterate columns instead of rows)"
+                    .to_string(),
+                summary: Summary {
+                    iterations: 1000,
+                    mean: Duration::from_nanos(123_456),
+                    std_dev: Duration::from_nanos(789),
+                },
+                recorded_at: 1_700_000_000,
+            },
+            Record {
+                group: "day3".to_string(),
+                label: "Part 2 (regex with \"do\"/don't)".to_string(),
+                summary: Summary {
+                    iterations: 10,
+                    mean: Duration::from_nanos(1),
+                    std_dev: Duration::ZERO,
+                },
+                recorded_at: 0,
+            },
+        ];
+
+        let path =
+            std::env::temp_dir().join(format!("aoc-baseline-test-{}.csv", std::process::id()));
+        save(&path, &records).unwrap();
+        let loaded = load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded, records);
+    }
+}
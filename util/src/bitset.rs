@@ -0,0 +1,144 @@
+// Fixed size sets of bits, from a single integer up to a heap allocated set of any size
+pub trait Bitset: Clone {
+    // The most bits with_capacity can make room for
+    const MAX_BITS: usize;
+
+    // An empty set with room for at least bits bits, panics if the type is too small
+    fn with_capacity(bits: usize) -> Self;
+
+    fn set(&mut self, bit: usize);
+
+    fn get(&self, bit: usize) -> bool;
+
+    // Whether every bit of mask is also set in self
+    fn contains_all(&self, mask: &Self) -> bool;
+
+    // Whether none of the bits of mask are set in self
+    fn contains_none(&self, mask: &Self) -> bool;
+}
+
+macro_rules! impl_bitset_int {
+    ($($t:ty),*) => {
+        $(
+            impl Bitset for $t {
+                const MAX_BITS: usize = <$t>::BITS as usize;
+
+                fn with_capacity(bits: usize) -> Self {
+                    assert!(bits <= <$t>::BITS as usize, "{bits} bits do not fit in {}", stringify!($t));
+                    0
+                }
+
+                #[inline]
+                fn set(&mut self, bit: usize) {
+                    *self |= 1 << bit;
+                }
+
+                #[inline]
+                fn get(&self, bit: usize) -> bool {
+                    self >> bit & 1 == 1
+                }
+
+                #[inline]
+                fn contains_all(&self, mask: &Self) -> bool {
+                    self & mask == *mask
+                }
+
+                #[inline]
+                fn contains_none(&self, mask: &Self) -> bool {
+                    self & mask == 0
+                }
+            }
+        )*
+    };
+}
+
+impl_bitset_int!(u8, u16, u32, u64, u128);
+
+impl<const N: usize> Bitset for [u64; N] {
+    const MAX_BITS: usize = N * 64;
+
+    fn with_capacity(bits: usize) -> Self {
+        assert!(bits <= N * 64, "{bits} bits do not fit in [u64; {N}]");
+        [0; N]
+    }
+
+    #[inline]
+    fn set(&mut self, bit: usize) {
+        self[bit / 64].set(bit % 64);
+    }
+
+    #[inline]
+    fn get(&self, bit: usize) -> bool {
+        self[bit / 64].get(bit % 64)
+    }
+
+    fn contains_all(&self, mask: &Self) -> bool {
+        self.iter().zip(mask).all(|(a, m)| a.contains_all(m))
+    }
+
+    fn contains_none(&self, mask: &Self) -> bool {
+        self.iter().zip(mask).all(|(a, m)| a.contains_none(m))
+    }
+}
+
+// Sets of any size, both sides of contains_* should have been made with the same capacity
+impl Bitset for Vec<u64> {
+    const MAX_BITS: usize = usize::MAX;
+
+    fn with_capacity(bits: usize) -> Self {
+        vec![0; bits.div_ceil(64)]
+    }
+
+    #[inline]
+    fn set(&mut self, bit: usize) {
+        self[bit / 64].set(bit % 64);
+    }
+
+    #[inline]
+    fn get(&self, bit: usize) -> bool {
+        self.as_slice()
+            .get(bit / 64)
+            .is_some_and(|w| w.get(bit % 64))
+    }
+
+    fn contains_all(&self, mask: &Self) -> bool {
+        self.iter().zip(mask).all(|(a, m)| a.contains_all(m))
+    }
+
+    fn contains_none(&self, mask: &Self) -> bool {
+        self.iter().zip(mask).all(|(a, m)| a.contains_none(m))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check<B: Bitset>(bits: usize) {
+        let mut a = B::with_capacity(bits);
+        let mut mask = B::with_capacity(bits);
+        for bit in [0, bits / 2, bits - 1] {
+            a.set(bit);
+        }
+        mask.set(bits - 1);
+        assert!(a.get(bits - 1) && !a.get(1));
+        assert!(a.contains_all(&mask));
+        assert!(!mask.contains_all(&a));
+
+        let mut other = B::with_capacity(bits);
+        other.set(1);
+        assert!(a.contains_none(&other));
+        assert!(!a.contains_none(&mask));
+    }
+
+    #[test]
+    fn test_bitsets() {
+        check::<u32>(32);
+        check::<u128>(100);
+        check::<[u64; 3]>(150);
+        check::<Vec<u64>>(1000);
+
+        assert_eq!(<[u64; 3]>::MAX_BITS, 192);
+        assert!(std::panic::catch_unwind(|| u32::with_capacity(33)).is_err());
+    }
+}
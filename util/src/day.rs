@@ -0,0 +1,41 @@
+use crate::{Answer, ParseError};
+
+pub struct Solver<'a> {
+    pub part: u8,
+    // Short name used to pick the solver on the command line, e.g. "reduced"
+    pub variant: &'static str,
+    // Descriptive label used when printing and measuring
+    pub label: &'static str,
+    pub run: Box<dyn Fn() -> Answer + 'a>,
+}
+
+impl<'a> Solver<'a> {
+    pub fn new<T: Into<Answer>, F: Fn() -> T + 'a>(
+        part: u8,
+        variant: &'static str,
+        label: &'static str,
+        f: F,
+    ) -> Self {
+        Solver {
+            part,
+            variant,
+            label,
+            run: Box::new(move || f().into()),
+        }
+    }
+}
+
+// Implemented by each day so a single runner can find and dispatch its solvers
+pub trait Day: Sync {
+    fn number(&self) -> u8;
+
+    // Crate directory holding input.txt and the sample inputs
+    fn dir(&self) -> &'static str;
+
+    // Parses the input once and returns every solver, primary variant first for each part
+    fn solvers<'a>(&self, input: &'a str) -> Result<Vec<Solver<'a>>, ParseError>;
+
+    fn name(&self) -> String {
+        format!("day{}", self.number())
+    }
+}
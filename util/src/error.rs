@@ -0,0 +1,117 @@
+use std::{error::Error, fmt};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    // 1-based line and column (in characters) of the offending text
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub reason: String,
+    // The whole line the error is on, to point at it when displaying
+    pub source_line: String,
+}
+
+impl ParseError {
+    // Builds an error for the byte range offset..offset + len of input
+    pub fn at_offset(input: &str, offset: usize, len: usize, reason: impl Into<String>) -> Self {
+        let offset = floor_char_boundary(input, offset.min(input.len()));
+        let end = floor_char_boundary(input, (offset + len).min(input.len()));
+
+        let line_start = input[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |i| offset + i);
+
+        ParseError {
+            line: input[..offset].matches('\n').count() + 1,
+            column: input[line_start..offset].chars().count() + 1,
+            text: input[offset..end].to_string(),
+            reason: reason.into(),
+            source_line: input[line_start..line_end]
+                .trim_end_matches('\r')
+                .to_string(),
+        }
+    }
+
+    // Builds an error for span, which has to be a slice of input (e.g. from split or lines)
+    pub fn at(input: &str, span: &str, reason: impl Into<String>) -> Self {
+        let offset = (span.as_ptr() as usize).wrapping_sub(input.as_ptr() as usize);
+        assert!(
+            offset <= input.len(),
+            "Span {span:?} is not part of the input"
+        );
+        Self::at_offset(input, offset, span.len(), reason)
+    }
+
+    // Builds an error just past the end of the input, for when it is cut short
+    pub fn at_end(input: &str, reason: impl Into<String>) -> Self {
+        let trimmed = input.trim_end();
+        Self::at_offset(input, trimmed.len(), 0, reason)
+    }
+
+    // For errors built from a single line, moves them to the line's actual position
+    pub fn on_line(mut self, line: usize) -> Self {
+        self.line = line;
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.reason
+        )?;
+        if !self.text.is_empty() {
+            write!(f, ", found \"{}\"", self.text.escape_debug())?;
+        }
+
+        let gutter = " ".repeat(self.line.to_string().len());
+        let indent = self
+            .source_line
+            .chars()
+            .take(self.column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect::<String>();
+        let marker = "^".repeat(self.text.chars().count().max(1));
+
+        write!(
+            f,
+            "\n{gutter} |\n{} | {}\n{gutter} | {indent}{marker}",
+            self.line, self.source_line
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+fn floor_char_boundary(s: &str, mut index: usize) -> usize {
+    while !s.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position() {
+        let input = "1 2\r\n3 4x\r\n5";
+        let token = input.split_ascii_whitespace().nth(3).unwrap();
+        let e = ParseError::at(input, token, "expected an integer");
+
+        assert_eq!((e.line, e.column), (2, 3));
+        assert_eq!(e.text, "4x");
+        assert_eq!(e.source_line, "3 4x");
+        assert_eq!(
+            e.to_string(),
+            "line 2, column 3: expected an integer, found \"4x\"\n  |\n2 | 3 4x\n  |   ^^"
+        );
+
+        let e = ParseError::at_end(input, "missing value");
+        assert_eq!((e.line, e.column), (3, 2));
+    }
+}
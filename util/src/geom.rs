@@ -0,0 +1,260 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub};
+
+// Screen coordinates, x grows to the right and y grows downwards
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point {
+    pub x: isize,
+    pub y: isize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Vector {
+    pub dx: isize,
+    pub dy: isize,
+}
+
+impl Point {
+    pub const fn new(x: isize, y: isize) -> Self {
+        Point { x, y }
+    }
+
+    pub fn from_index((x, y): (usize, usize)) -> Self {
+        Point::new(x as isize, y as isize)
+    }
+
+    // The (x, y) index into a grid of the given size, None if outside of it
+    pub fn to_index(self, width: usize, height: usize) -> Option<(usize, usize)> {
+        let (x, y) = (usize::try_from(self.x).ok()?, usize::try_from(self.y).ok()?);
+        (x < width && y < height).then_some((x, y))
+    }
+
+    pub fn step(self, direction: Direction) -> Self {
+        self + direction.delta()
+    }
+
+    // Steps in direction, None if that leaves a grid of the given size
+    pub fn step_within(self, direction: Direction, width: usize, height: usize) -> Option<Self> {
+        let next = self.step(direction);
+        next.to_index(width, height).map(|_| next)
+    }
+
+    pub fn manhattan(self, other: Point) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+}
+
+impl Vector {
+    pub const fn new(dx: isize, dy: isize) -> Self {
+        Vector { dx, dy }
+    }
+
+    // Quarter turn clockwise on screen
+    pub fn rotate_right(self) -> Self {
+        Vector::new(-self.dy, self.dx)
+    }
+
+    // Quarter turn counter-clockwise on screen
+    pub fn rotate_left(self) -> Self {
+        Vector::new(self.dy, -self.dx)
+    }
+}
+
+impl Add<Vector> for Point {
+    type Output = Point;
+
+    fn add(self, rhs: Vector) -> Point {
+        Point::new(self.x + rhs.dx, self.y + rhs.dy)
+    }
+}
+
+impl AddAssign<Vector> for Point {
+    fn add_assign(&mut self, rhs: Vector) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub<Vector> for Point {
+    type Output = Point;
+
+    fn sub(self, rhs: Vector) -> Point {
+        self + -rhs
+    }
+}
+
+impl Sub for Point {
+    type Output = Vector;
+
+    fn sub(self, rhs: Point) -> Vector {
+        Vector::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl Add for Vector {
+    type Output = Vector;
+
+    fn add(self, rhs: Vector) -> Vector {
+        Vector::new(self.dx + rhs.dx, self.dy + rhs.dy)
+    }
+}
+
+impl Neg for Vector {
+    type Output = Vector;
+
+    fn neg(self) -> Vector {
+        Vector::new(-self.dx, -self.dy)
+    }
+}
+
+impl Mul<isize> for Vector {
+    type Output = Vector;
+
+    fn mul(self, rhs: isize) -> Vector {
+        Vector::new(self.dx * rhs, self.dy * rhs)
+    }
+}
+
+// The eight compass directions, in clockwise order starting from Right
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+    Up,
+    UpRight,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 8] = [
+        Direction::Right,
+        Direction::DownRight,
+        Direction::Down,
+        Direction::DownLeft,
+        Direction::Left,
+        Direction::UpLeft,
+        Direction::Up,
+        Direction::UpRight,
+    ];
+
+    pub const ORTHOGONAL: [Direction; 4] = [
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+        Direction::Up,
+    ];
+
+    pub const DIAGONAL: [Direction; 4] = [
+        Direction::DownRight,
+        Direction::DownLeft,
+        Direction::UpLeft,
+        Direction::UpRight,
+    ];
+
+    // One of each pair of opposite directions, enough to find a line read either way
+    pub const REDUCED: [Direction; 4] = [
+        Direction::Right,
+        Direction::DownRight,
+        Direction::Down,
+        Direction::DownLeft,
+    ];
+
+    pub fn iter_all() -> impl Iterator<Item = Direction> {
+        Self::ALL.iter().copied()
+    }
+
+    pub fn iter_orthogonal() -> impl Iterator<Item = Direction> {
+        Self::ORTHOGONAL.iter().copied()
+    }
+
+    pub fn iter_reduced() -> impl Iterator<Item = Direction> {
+        Self::REDUCED.iter().copied()
+    }
+
+    pub fn delta(self) -> Vector {
+        let (dx, dy) = match self {
+            Direction::Right => (1, 0),
+            Direction::DownRight => (1, 1),
+            Direction::Down => (0, 1),
+            Direction::DownLeft => (-1, 1),
+            Direction::Left => (-1, 0),
+            Direction::UpLeft => (-1, -1),
+            Direction::Up => (0, -1),
+            Direction::UpRight => (1, -1),
+        };
+        Vector::new(dx, dy)
+    }
+
+    fn rotate(self, eighths: usize) -> Direction {
+        Self::ALL[(self as usize + eighths) % 8]
+    }
+
+    // Quarter turn clockwise
+    pub fn turn_right(self) -> Direction {
+        self.rotate(2)
+    }
+
+    // Quarter turn counter-clockwise
+    pub fn turn_left(self) -> Direction {
+        self.rotate(6)
+    }
+
+    // Eighth turn clockwise, e.g. Right to DownRight
+    pub fn turn_right_45(self) -> Direction {
+        self.rotate(1)
+    }
+
+    // Eighth turn counter-clockwise, e.g. Right to UpRight
+    pub fn turn_left_45(self) -> Direction {
+        self.rotate(7)
+    }
+
+    pub fn opposite(self) -> Direction {
+        self.rotate(4)
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self as usize % 2 == 1
+    }
+
+    // Whether len cells starting at (x, y) and going this way stay within the grid
+    pub fn ray_fits(self, len: usize, x: usize, y: usize, width: usize, height: usize) -> bool {
+        if len == 0 {
+            return true;
+        }
+        let end = Point::from_index((x, y)) + self.delta() * (len as isize - 1);
+        x < width && y < height && end.to_index(width, height).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turns() {
+        for d in Direction::iter_all() {
+            assert_eq!(d.turn_right().delta(), d.delta().rotate_right());
+            assert_eq!(d.turn_left().delta(), d.delta().rotate_left());
+            assert_eq!(d.opposite().delta(), -d.delta());
+            assert_eq!(d.turn_right_45().turn_left_45(), d);
+        }
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert!(Direction::UpLeft.is_diagonal());
+    }
+
+    #[test]
+    fn test_stepping() {
+        let p = Point::new(0, 1);
+        assert_eq!(p.step(Direction::DownRight), Point::new(1, 2));
+        assert_eq!(p.step_within(Direction::Left, 3, 3), None);
+        assert_eq!(p.step_within(Direction::Up, 3, 3), Some(Point::new(0, 0)));
+        assert_eq!(Point::new(3, 4) - Point::new(1, 1), Vector::new(2, 3));
+
+        assert!(Direction::Right.ray_fits(4, 0, 0, 4, 1));
+        assert!(!Direction::Right.ray_fits(4, 1, 0, 4, 1));
+        assert!(Direction::UpLeft.ray_fits(2, 1, 1, 2, 2));
+        assert!(!Direction::UpLeft.ray_fits(3, 1, 1, 2, 2));
+    }
+}
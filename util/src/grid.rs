@@ -0,0 +1,373 @@
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+use crate::{
+    geom::{Direction, Point},
+    parse::Cursor,
+    ParseError,
+};
+
+// Row-major 2D grid, indexed with (x, y)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    pub width: usize,
+    pub height: usize,
+    pub entries: Vec<T>,
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, index: (usize, usize)) -> &Self::Output {
+        &self.entries[index.1 * self.width + index.0]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, index: (usize, usize)) -> &mut Self::Output {
+        &mut self.entries[index.1 * self.width + index.0]
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &Self::Output {
+        self.get_point(p)
+            .unwrap_or_else(|| panic!("{p:?} is outside the grid"))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut Self::Output {
+        let index = p
+            .to_index(self.width, self.height)
+            .unwrap_or_else(|| panic!("{p:?} is outside the grid"));
+        &mut self[index]
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn from_vec(width: usize, height: usize, entries: Vec<T>) -> Self {
+        assert_eq!(width * height, entries.len(), "Grid size mismatch");
+        Grid {
+            width,
+            height,
+            entries,
+        }
+    }
+
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(usize, usize) -> T) -> Self {
+        let entries = (0..width * height)
+            .map(|i| f(i % width, i / width))
+            .collect();
+        Self::from_vec(width, height, entries)
+    }
+
+    pub fn in_bounds(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        self.in_bounds(x, y).then(|| &self[(x, y)])
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        self.in_bounds(x, y).then(|| &mut self[(x, y)])
+    }
+
+    pub fn get_point(&self, p: Point) -> Option<&T> {
+        p.to_index(self.width, self.height)
+            .map(|index| &self[index])
+    }
+
+    // Steps from p in direction, None if that leaves the grid
+    pub fn step(&self, p: Point, direction: Direction) -> Option<Point> {
+        p.step_within(direction, self.width, self.height)
+    }
+
+    // Steps by (dx, dy) from (x, y), None if that leaves the grid
+    pub fn offset(&self, x: usize, y: usize, dx: isize, dy: isize) -> Option<(usize, usize)> {
+        let (nx, ny) = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+        self.in_bounds(nx, ny).then_some((nx, ny))
+    }
+
+    // Positions of the up to 4 orthogonal neighbours inside the grid
+    pub fn neighbours4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbours(x, y, &Direction::ORTHOGONAL)
+    }
+
+    // Positions of the up to 8 orthogonal and diagonal neighbours inside the grid
+    pub fn neighbours8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbours(x, y, &Direction::ALL)
+    }
+
+    pub fn neighbours<'a>(
+        &'a self,
+        x: usize,
+        y: usize,
+        directions: &'a [Direction],
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        directions.iter().filter_map(move |d| {
+            let delta = d.delta();
+            self.offset(x, y, delta.dx, delta.dy)
+        })
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.entries.len()).map(move |i| (i % width, i / width))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.entries[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.entries.chunks_exact(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.entries.iter().skip(x).step_by(self.width.max(1))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    // Walks from (x, y) by (dx, dy) until leaving the grid
+    pub fn ray(&self, x: usize, y: usize, dx: isize, dy: isize) -> impl Iterator<Item = &T> {
+        let mut next = self.get(x, y).map(|_| (x, y));
+        std::iter::from_fn(move || {
+            let (x, y) = next?;
+            next = self.offset(x, y, dx, dy);
+            Some(&self[(x, y)])
+        })
+    }
+
+    // Top-left to bottom-right diagonals, starting from the bottom-left corner
+    pub fn diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        let starts = (0..self.height)
+            .rev()
+            .map(|y| (0, y))
+            .chain((1..self.width).map(|x| (x, 0)));
+        starts.map(move |(x, y)| self.ray(x, y, 1, 1))
+    }
+
+    // Top-right to bottom-left diagonals, starting from the top-left corner
+    pub fn anti_diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        let last = self.width.saturating_sub(1);
+        let starts = (0..self.width)
+            .map(|x| (x, 0))
+            .chain((1..self.height).map(move |y| (last, y)));
+        starts.map(move |(x, y)| self.ray(x, y, -1, 1))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid::from_vec(
+            self.width,
+            self.height,
+            self.entries.iter().map(f).collect(),
+        )
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Self::from_vec(width, height, vec![fill; width * height])
+    }
+
+    pub fn transpose(&self) -> Self {
+        Grid::from_fn(self.height, self.width, |x, y| self[(y, x)].clone())
+    }
+
+    // Quarter turn clockwise
+    pub fn rotate_right(&self) -> Self {
+        Grid::from_fn(self.height, self.width, |x, y| {
+            self[(y, self.height - 1 - x)].clone()
+        })
+    }
+
+    // Quarter turn counter-clockwise
+    pub fn rotate_left(&self) -> Self {
+        Grid::from_fn(self.height, self.width, |x, y| {
+            self[(self.width - 1 - y, x)].clone()
+        })
+    }
+
+    // Mirrors left to right
+    pub fn flip_horizontal(&self) -> Self {
+        Grid::from_fn(self.width, self.height, |x, y| {
+            self[(self.width - 1 - x, y)].clone()
+        })
+    }
+
+    // Mirrors top to bottom
+    pub fn flip_vertical(&self) -> Self {
+        Grid::from_fn(self.width, self.height, |x, y| {
+            self[(x, self.height - 1 - y)].clone()
+        })
+    }
+}
+
+impl<T> Grid<T> {
+    // Parses one row per line, converting each character with f (None rejects it)
+    pub fn parse(input: &str, mut f: impl FnMut(char) -> Option<T>) -> Result<Self, ParseError> {
+        Self::parse_rows(input, |line, entries| {
+            for (i, c) in line.char_indices() {
+                let value = f(c).ok_or_else(|| {
+                    ParseError::at(input, &line[i..i + c.len_utf8()], "unexpected character")
+                })?;
+                entries.push(value);
+            }
+            Ok(line.chars().count())
+        })
+    }
+
+    // Splits input into lines for row, which adds the entries of a line and returns how many
+    fn parse_rows(
+        input: &str,
+        mut row: impl FnMut(&str, &mut Vec<T>) -> Result<usize, ParseError>,
+    ) -> Result<Self, ParseError> {
+        let mut width: Option<usize> = None;
+        let mut entries = vec![];
+
+        let mut height = 0;
+        // Spaces can be cells, so only the line endings after the last row are dropped
+        let mut cursor = Cursor::new(input.trim_end_matches(['\r', '\n']));
+        while let Some(line) = cursor.line() {
+            height += 1;
+            let line_width = row(line, &mut entries)?;
+
+            // Validate that they all have the same length
+            match width {
+                None => width = Some(line_width),
+                Some(width) if width != line_width => {
+                    let reason =
+                        format!("rows should be {} wide, this one is {}", width, line_width);
+                    return Err(ParseError::at(input, line, reason));
+                }
+                Some(_) => (),
+            }
+        }
+
+        match width {
+            Some(width) if width > 0 => Ok(Grid::from_vec(width, height, entries)),
+            _ => Err(ParseError::at_end(input, "expected a grid")),
+        }
+    }
+}
+
+impl Grid<u8> {
+    // Parses an ASCII grid keeping the raw bytes, a whole row at a time
+    pub fn parse_bytes(input: &str) -> Result<Self, ParseError> {
+        Self::parse_rows(input, |line, entries| {
+            if let Some(i) = line.bytes().position(|b| !b.is_ascii()) {
+                let c = line[i..].chars().next().unwrap();
+                let text = &line[i..i + c.len_utf8()];
+                return Err(ParseError::at(input, text, "unexpected character"));
+            }
+            entries.extend_from_slice(line.as_bytes());
+            Ok(line.len())
+        })
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Grid<char> {
+        Grid::parse("abc\ndef\n", Some).unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let grid = sample();
+        assert_eq!((grid.width, grid.height), (3, 2));
+        assert_eq!(grid[(2, 1)], 'f');
+        assert_eq!(grid.to_string(), "abc\ndef");
+
+        let e = Grid::parse_bytes("abc\nde\n").unwrap_err();
+        assert_eq!((e.line, e.column), (2, 1));
+        assert!(Grid::parse_bytes("\n").is_err());
+
+        // Trailing spaces are cells too
+        let spaces = Grid::parse("#  \r\n # \r\n\n", Some).unwrap();
+        assert_eq!((spaces.width, spaces.height), (3, 2));
+        assert_eq!(spaces.to_string(), "#  \n # ");
+    }
+
+    #[test]
+    fn test_iterators() {
+        let grid = sample();
+        let collect = |it: &mut dyn Iterator<Item = &char>| it.collect::<String>();
+
+        assert_eq!(
+            grid.rows()
+                .map(|r| r.iter().collect::<String>())
+                .collect::<Vec<_>>(),
+            ["abc", "def"]
+        );
+        assert_eq!(
+            grid.columns()
+                .map(|mut c| collect(&mut c))
+                .collect::<Vec<_>>(),
+            ["ad", "be", "cf"]
+        );
+        assert_eq!(
+            grid.diagonals()
+                .map(|mut d| collect(&mut d))
+                .collect::<Vec<_>>(),
+            ["d", "ae", "bf", "c"]
+        );
+        assert_eq!(
+            grid.anti_diagonals()
+                .map(|mut d| collect(&mut d))
+                .collect::<Vec<_>>(),
+            ["a", "bd", "ce", "f"]
+        );
+
+        assert_eq!(grid.neighbours4(0, 0).collect::<Vec<_>>(), [(1, 0), (0, 1)]);
+        assert_eq!(grid.neighbours8(1, 1).count(), 5);
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid[Point::new(1, 1)], 'e');
+        assert_eq!(
+            grid.step(Point::new(1, 1), Direction::Up),
+            Some(Point::new(1, 0))
+        );
+        assert_eq!(grid.step(Point::new(1, 1), Direction::Down), None);
+    }
+
+    #[test]
+    fn test_transforms() {
+        let grid = sample();
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf");
+        assert_eq!(grid.rotate_right().to_string(), "da\neb\nfc");
+        assert_eq!(grid.rotate_left().to_string(), "cf\nbe\nad");
+        assert_eq!(grid.flip_horizontal().to_string(), "cba\nfed");
+        assert_eq!(grid.flip_vertical().to_string(), "def\nabc");
+        assert_eq!(grid.rotate_right().rotate_left(), grid);
+
+        let mut grid = grid;
+        grid[(0, 0)] = 'z';
+        *grid.get_mut(1, 0).unwrap() = 'y';
+        assert_eq!(grid.to_string(), "zyc\ndef");
+    }
+}
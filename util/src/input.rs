@@ -0,0 +1,162 @@
+use std::{
+    fmt, fs,
+    io::{self, BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+};
+
+pub const USAGE: &str = "\
+Input options:
+  --input <PATH>    Reads the puzzle input from PATH, or from stdin if PATH is -
+  --sample <NAME>   Reads input_sample_<NAME>.txt (or input_sample.txt) from the day's directory
+
+The AOC_INPUT environment variable takes the same values, e.g. AOC_INPUT=sample:part1,
+defaulting to input.txt in the day's directory. Days that support it read the input as a
+stream instead of loading it whole when AOC_STREAM=1.";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    // input.txt in the day's directory
+    Default,
+    Path(PathBuf),
+    Stdin,
+    Sample(String),
+}
+
+impl InputSource {
+    // "-" is stdin, "sample:NAME" a sample and anything else a path
+    pub fn parse(spec: &str) -> Self {
+        match spec {
+            "-" => InputSource::Stdin,
+            _ => match spec.strip_prefix("sample:") {
+                Some(name) => InputSource::Sample(name.to_string()),
+                None => InputSource::Path(PathBuf::from(spec)),
+            },
+        }
+    }
+
+    pub fn from_env() -> Option<Self> {
+        std::env::var("AOC_INPUT")
+            .ok()
+            .filter(|v| !v.is_empty())
+            .map(|v| InputSource::parse(&v))
+    }
+
+    // Takes --input/--sample out of the arguments, returning what is left
+    pub fn from_args(
+        args: impl IntoIterator<Item = String>,
+    ) -> Result<(Option<Self>, Vec<String>), String> {
+        let mut source = None;
+        let mut rest = vec![];
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--input" | "-i" => {
+                    let value = args.next().ok_or("Missing value for --input")?;
+                    source = Some(InputSource::parse(&value));
+                }
+                "--sample" | "-s" => {
+                    let value = args.next().ok_or("Missing value for --sample")?;
+                    source = Some(InputSource::Sample(value));
+                }
+                _ => rest.push(arg),
+            }
+        }
+
+        Ok((source, rest))
+    }
+
+    // Relative paths given on the command line are relative to the working directory, while the
+    // default input and samples are looked up in the day's directory
+    pub fn resolve(&self, dir: &Path) -> Option<PathBuf> {
+        match self {
+            InputSource::Default => Some(dir.join("input.txt")),
+            InputSource::Path(path) => Some(path.clone()),
+            InputSource::Stdin => None,
+            InputSource::Sample(name) => {
+                let specific = dir.join(format!("input_sample_{name}.txt"));
+                let shared = dir.join("input_sample.txt");
+                Some(if specific.exists() || !shared.exists() {
+                    specific
+                } else {
+                    shared
+                })
+            }
+        }
+    }
+
+    pub fn read(&self, dir: &Path) -> io::Result<String> {
+        let mut input = String::new();
+        self.open(dir)?.read_to_string(&mut input)?;
+        Ok(input)
+    }
+
+    // Opens the input for reading it bit by bit, for inputs too big to read whole
+    pub fn open(&self, dir: &Path) -> io::Result<Box<dyn BufRead>> {
+        match self.resolve(dir) {
+            Some(path) => match fs::File::open(&path) {
+                Ok(file) => Ok(Box::new(BufReader::new(file))),
+                Err(e) => Err(io::Error::new(
+                    e.kind(),
+                    format!("Unable to read {}: {}", path.display(), e),
+                )),
+            },
+            None => Ok(Box::new(io::stdin().lock())),
+        }
+    }
+}
+
+impl fmt::Display for InputSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputSource::Default => write!(f, "input.txt"),
+            InputSource::Path(path) => write!(f, "{}", path.display()),
+            InputSource::Stdin => write!(f, "stdin"),
+            InputSource::Sample(name) => write!(f, "sample {name}"),
+        }
+    }
+}
+
+// Reads the input for a day binary from its arguments, AOC_INPUT or input.txt, exiting on errors
+pub fn load(dir: &str) -> String {
+    match source().read(Path::new(dir)) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+// Like load, but opens the input to stream it
+pub fn open(dir: &str) -> Box<dyn BufRead> {
+    match source().open(Path::new(dir)) {
+        Ok(reader) => reader,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+// Whether AOC_STREAM asks day binaries to stream their input
+pub fn streaming() -> bool {
+    std::env::var("AOC_STREAM").is_ok_and(|v| !v.is_empty() && v != "0")
+}
+
+fn source() -> InputSource {
+    let source = match InputSource::from_args(std::env::args().skip(1)) {
+        Ok((source, rest)) if rest.is_empty() => source,
+        Ok((_, rest)) => exit_with_usage(&format!("Unknown argument: {}", rest[0])),
+        Err(e) => exit_with_usage(&e),
+    };
+
+    source
+        .or_else(InputSource::from_env)
+        .unwrap_or(InputSource::Default)
+}
+
+fn exit_with_usage(message: &str) -> ! {
+    eprintln!("{message}\n\n{USAGE}");
+    std::process::exit(2);
+}
@@ -1,25 +1,24 @@
-use std::{hint::black_box, time, fmt::Debug};
+pub mod baseline;
+pub mod bitset;
+pub mod day;
+pub mod error;
+pub mod geom;
+pub mod grid;
+pub mod input;
+pub mod measure;
+pub mod parallel;
+pub mod parse;
+pub mod solution;
+pub mod stencil;
+pub mod stream;
+pub mod synthetic;
+pub mod testing;
+pub mod wordsearch;
 
-pub fn measure<T : Debug, F: Fn() -> T>(label: &str, iterations: usize, f: F) {
-    let mut times = Vec::new();
-
-    // Warm up
-    #[allow(clippy::unit_arg)]
-    let result = black_box(f());
-
-    for _ in 0..iterations {
-        let start = time::Instant::now();
-        #[allow(clippy::unit_arg)]
-        black_box(f());
-        let end = time::Instant::now();
-        times.push(end - start);
-    }
-
-    times.sort();
-    let (min, median, max) = (times[0], times[iterations / 2], times[iterations - 1]);
-
-    println!("{}: {:?}", label, result);
-
-    println!("Median time: {:?}   (min: {:?} / max: {:?})", median, min, max);
-    println!();
-}
+pub use day::{Day, Solver};
+pub use error::ParseError;
+pub use grid::Grid;
+pub use measure::{measure, measure_with, Measurement};
+pub use solution::{
+    bench, bench_scaling, check_agreement, check_parts, Answer, Disagreement, Solution, Variant,
+};
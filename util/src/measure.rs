@@ -0,0 +1,483 @@
+use std::{fmt, hint::black_box, time::Duration, time::Instant};
+
+use crate::{
+    baseline::{self, BaselineComparison},
+    parallel,
+};
+
+#[derive(Debug, Clone)]
+pub struct MeasureConfig {
+    // Rough wall-clock budget for the timed iterations
+    pub target: Duration,
+    pub warm_up: Duration,
+    pub min_iterations: usize,
+    pub max_iterations: usize,
+}
+
+impl Default for MeasureConfig {
+    fn default() -> Self {
+        MeasureConfig {
+            target: Duration::from_millis(500),
+            warm_up: Duration::from_millis(100),
+            min_iterations: 10,
+            max_iterations: 100_000,
+        }
+    }
+}
+
+impl MeasureConfig {
+    // AOC_MEASURE_MS overrides the target budget, handy for quick runs
+    pub fn from_env() -> Self {
+        let mut config = MeasureConfig::default();
+        if let Some(ms) = std::env::var("AOC_MEASURE_MS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+        {
+            config.target = Duration::from_millis(ms);
+            config.warm_up = config.target / 5;
+        }
+        config
+    }
+
+    fn iterations_for(&self, per_iteration: Duration) -> usize {
+        let per_iteration = per_iteration.as_secs_f64().max(1e-9);
+        let iterations = (self.target.as_secs_f64() / per_iteration) as usize;
+        iterations.clamp(self.min_iterations, self.max_iterations)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Outliers {
+    pub low_severe: usize,
+    pub low_mild: usize,
+    pub high_mild: usize,
+    pub high_severe: usize,
+}
+
+impl Outliers {
+    pub fn total(&self) -> usize {
+        self.low_severe + self.low_mild + self.high_mild + self.high_severe
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Measurement<T> {
+    pub label: String,
+    pub result: T,
+    pub iterations: usize,
+    pub mean: Duration,
+    pub std_dev: Duration,
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+    pub p5: Duration,
+    pub p95: Duration,
+    pub p99: Duration,
+    // 95% confidence interval of the mean
+    pub mean_ci: (Duration, Duration),
+    pub outliers: Outliers,
+    pub baseline: Option<BaselineComparison>,
+}
+
+impl<T> Measurement<T> {
+    pub fn from_samples(label: &str, result: T, mut samples: Vec<Duration>) -> Self {
+        assert!(!samples.is_empty(), "No samples for {label}");
+        samples.sort();
+
+        let secs = samples
+            .iter()
+            .map(Duration::as_secs_f64)
+            .collect::<Vec<_>>();
+        let n = secs.len();
+        let mean = secs.iter().sum::<f64>() / n as f64;
+        let variance = if n > 1 {
+            secs.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (n - 1) as f64
+        } else {
+            0.0
+        };
+        let std_dev = variance.sqrt();
+        let half_width = t_critical_95(n.saturating_sub(1)) * std_dev / (n as f64).sqrt();
+
+        Measurement {
+            label: label.to_string(),
+            result,
+            iterations: n,
+            mean: secs_to_duration(mean),
+            std_dev: secs_to_duration(std_dev),
+            min: samples[0],
+            median: secs_to_duration(percentile(&secs, 50.0)),
+            max: samples[n - 1],
+            p5: secs_to_duration(percentile(&secs, 5.0)),
+            p95: secs_to_duration(percentile(&secs, 95.0)),
+            p99: secs_to_duration(percentile(&secs, 99.0)),
+            mean_ci: (
+                secs_to_duration(mean - half_width),
+                secs_to_duration(mean + half_width),
+            ),
+            outliers: classify_outliers(&secs),
+            baseline: None,
+        }
+    }
+
+    pub fn summary(&self) -> Summary {
+        Summary {
+            iterations: self.iterations,
+            mean: self.mean,
+            std_dev: self.std_dev,
+        }
+    }
+
+    pub fn compare<U>(&self, other: &Measurement<U>) -> Comparison {
+        self.summary().compare(&other.summary())
+    }
+
+    // Items processed per second, going by the median time
+    pub fn throughput(&self, items: usize) -> f64 {
+        items as f64 / self.median.as_secs_f64().max(1e-12)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Display for Measurement<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}: {:?}", self.label, self.result)?;
+        writeln!(
+            f,
+            "Median time: {:?}   (min: {:?} / max: {:?})",
+            self.median, self.min, self.max
+        )?;
+        writeln!(
+            f,
+            "Mean time: {:?} ± {:?}   (95% CI: {:?} .. {:?})",
+            self.mean, self.std_dev, self.mean_ci.0, self.mean_ci.1
+        )?;
+        write!(
+            f,
+            "Percentiles: p5 {:?} / p95 {:?} / p99 {:?}   ({} iterations",
+            self.p5, self.p95, self.p99, self.iterations
+        )?;
+        let o = &self.outliers;
+        if o.total() > 0 {
+            write!(
+                f,
+                ", {} outliers: {} low severe, {} low mild, {} high mild, {} high severe",
+                o.total(),
+                o.low_severe,
+                o.low_mild,
+                o.high_mild,
+                o.high_severe
+            )?;
+        }
+        write!(f, ")")?;
+        if let Some(baseline) = &self.baseline {
+            write!(f, "\n{}", baseline)?;
+        }
+        Ok(())
+    }
+}
+
+// The bits of a measurement needed to compare it with another one
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Summary {
+    pub iterations: usize,
+    pub mean: Duration,
+    pub std_dev: Duration,
+}
+
+impl Summary {
+    // Welch's t-test of self against a baseline
+    pub fn compare(&self, baseline: &Summary) -> Comparison {
+        let (m1, m2) = (self.mean.as_secs_f64(), baseline.mean.as_secs_f64());
+        let (n1, n2) = (
+            self.iterations.max(1) as f64,
+            baseline.iterations.max(1) as f64,
+        );
+        let (v1, v2) = (
+            self.std_dev.as_secs_f64().powi(2) / n1,
+            baseline.std_dev.as_secs_f64().powi(2) / n2,
+        );
+
+        let std_err = (v1 + v2).sqrt();
+        let t = if std_err > 0.0 {
+            (m1 - m2) / std_err
+        } else {
+            0.0
+        };
+        let df = if v1 + v2 > 0.0 {
+            (v1 + v2).powi(2)
+                / (v1.powi(2) / (n1 - 1.0).max(1.0) + v2.powi(2) / (n2 - 1.0).max(1.0))
+        } else {
+            1.0
+        };
+
+        Comparison {
+            change: if m2 > 0.0 { (m1 - m2) / m2 } else { 0.0 },
+            t,
+            significant: std_err > 0.0 && t.abs() > t_critical_95(df as usize),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Comparison {
+    // Relative change of the mean, e.g. -0.25 is 25% faster
+    pub change: f64,
+    pub t: f64,
+    pub significant: bool,
+}
+
+impl Comparison {
+    pub fn is_faster(&self) -> bool {
+        self.significant && self.change < 0.0
+    }
+
+    pub fn is_slower(&self) -> bool {
+        self.significant && self.change > 0.0
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verdict = if self.is_faster() {
+            "faster"
+        } else if self.is_slower() {
+            "slower"
+        } else {
+            "no significant difference"
+        };
+        write!(
+            f,
+            "{:+.2}% ({}, t = {:.2})",
+            self.change * 100.0,
+            verdict,
+            self.t
+        )
+    }
+}
+
+pub fn measure<T: fmt::Debug, F: Fn() -> T>(label: &str, f: F) -> Measurement<T> {
+    measure_with(label, &MeasureConfig::from_env(), f)
+}
+
+pub fn measure_with<T: fmt::Debug, F: Fn() -> T>(
+    label: &str,
+    config: &MeasureConfig,
+    f: F,
+) -> Measurement<T> {
+    // Warm up, and use that to estimate how many iterations fit in the budget
+    let warm_up_start = Instant::now();
+    #[allow(clippy::unit_arg)]
+    let result = black_box(f());
+    let mut warm_up_runs = 1;
+    while warm_up_start.elapsed() < config.warm_up {
+        #[allow(clippy::unit_arg)]
+        black_box(f());
+        warm_up_runs += 1;
+    }
+    let iterations = config.iterations_for(warm_up_start.elapsed() / warm_up_runs);
+
+    let mut times = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let start = Instant::now();
+        #[allow(clippy::unit_arg)]
+        black_box(f());
+        let end = Instant::now();
+        times.push(end - start);
+    }
+
+    let mut measurement = Measurement::from_samples(label, result, times);
+    measurement.baseline = baseline::record(label, &measurement.summary());
+    println!("{}", measurement);
+    println!();
+
+    measurement
+}
+
+// How a parallel solver speeds up with more threads
+#[derive(Debug, Clone)]
+pub struct Scaling<T> {
+    pub label: String,
+    // Size of the work, e.g. lines or cells, to report throughput in
+    pub items: usize,
+    pub measurements: Vec<(usize, Measurement<T>)>,
+}
+
+impl<T> Scaling<T> {
+    // Median time with one thread over median time with threads, None if not measured
+    pub fn speedup(&self, threads: usize) -> Option<f64> {
+        let time = |n| {
+            self.measurements
+                .iter()
+                .find(|(t, _)| *t == n)
+                .map(|(_, m)| m.median.as_secs_f64())
+        };
+        Some(time(1)? / time(threads)?.max(1e-12))
+    }
+}
+
+impl<T> fmt::Display for Scaling<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Scaling of {} over {} items:", self.label, self.items)?;
+        write!(
+            f,
+            "{:>8} {:>14} {:>14} {:>9} {:>11}",
+            "threads", "median", "items/s", "speedup", "efficiency"
+        )?;
+        for (threads, m) in &self.measurements {
+            write!(
+                f,
+                "\n{:>8} {:>14} {:>14} ",
+                threads,
+                format!("{:.2?}", m.median),
+                format_si(m.throughput(self.items))
+            )?;
+            match self.speedup(*threads) {
+                Some(speedup) => write!(
+                    f,
+                    "{:>8.2}x {:>10.0}%",
+                    speedup,
+                    speedup / *threads as f64 * 100.0
+                )?,
+                None => write!(f, "{:>9} {:>11}", "-", "-")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+// Measures f with each of the thread counts, then prints how its throughput scales
+pub fn measure_scaling<T: fmt::Debug, F: Fn() -> T>(
+    label: &str,
+    items: usize,
+    thread_counts: &[usize],
+    f: F,
+) -> Scaling<T> {
+    let config = MeasureConfig::from_env();
+    let measurements = thread_counts
+        .iter()
+        .map(|&threads| {
+            parallel::set_threads(threads);
+            let label = format!("{label} ({threads} threads)");
+            (threads, measure_with(&label, &config, &f))
+        })
+        .collect();
+    parallel::set_threads(0);
+
+    let scaling = Scaling {
+        label: label.to_string(),
+        items,
+        measurements,
+    };
+    println!("{}", scaling);
+    println!();
+
+    scaling
+}
+
+// 1234567.0 as 1.23M
+fn format_si(value: f64) -> String {
+    const PREFIXES: [(f64, &str); 4] = [(1e12, "T"), (1e9, "G"), (1e6, "M"), (1e3, "k")];
+    match PREFIXES.iter().find(|(scale, _)| value >= *scale) {
+        Some((scale, prefix)) => format!("{:.2}{}", value / scale, prefix),
+        None => format!("{:.2}", value),
+    }
+}
+
+fn secs_to_duration(secs: f64) -> Duration {
+    Duration::from_secs_f64(secs.max(0.0))
+}
+
+// Linear interpolation between closest ranks, expects sorted input
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = p / 100.0 * (sorted.len() - 1) as f64;
+    let (lo, hi) = (rank.floor() as usize, rank.ceil() as usize);
+    sorted[lo] + (sorted[hi] - sorted[lo]) * (rank - lo as f64)
+}
+
+// Tukey's fences, 1.5 IQR for mild and 3 IQR for severe outliers
+fn classify_outliers(sorted: &[f64]) -> Outliers {
+    let (q1, q3) = (percentile(sorted, 25.0), percentile(sorted, 75.0));
+    let iqr = q3 - q1;
+    let (low_severe, low_mild) = (q1 - 3.0 * iqr, q1 - 1.5 * iqr);
+    let (high_mild, high_severe) = (q3 + 1.5 * iqr, q3 + 3.0 * iqr);
+
+    let mut outliers = Outliers::default();
+    for &s in sorted {
+        if s < low_severe {
+            outliers.low_severe += 1;
+        } else if s < low_mild {
+            outliers.low_mild += 1;
+        } else if s > high_severe {
+            outliers.high_severe += 1;
+        } else if s > high_mild {
+            outliers.high_mild += 1;
+        }
+    }
+    outliers
+}
+
+// Two-sided 95% critical value of Student's t distribution
+fn t_critical_95(df: usize) -> f64 {
+    const TABLE: [f64; 30] = [
+        12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160,
+        2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056,
+        2.052, 2.048, 2.045, 2.042,
+    ];
+    match df {
+        0 => f64::INFINITY,
+        1..=30 => TABLE[df - 1],
+        31..=60 => 2.000,
+        61..=120 => 1.980,
+        _ => 1.960,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn micros(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|v| Duration::from_micros(*v)).collect()
+    }
+
+    #[test]
+    fn test_statistics() {
+        let samples = micros(&[10, 12, 11, 10, 13, 11, 12, 10, 11, 100]);
+        let m = Measurement::from_samples("test", (), samples);
+
+        assert_eq!(m.iterations, 10);
+        assert_eq!(m.min, Duration::from_micros(10));
+        assert_eq!(m.max, Duration::from_micros(100));
+        assert_eq!(m.median, Duration::from_micros(11));
+        assert_eq!(m.mean, Duration::from_micros(20));
+        assert!(m.mean_ci.0 < m.mean && m.mean < m.mean_ci.1);
+        assert_eq!(m.outliers.high_severe, 1);
+        assert_eq!(m.outliers.total(), 1);
+    }
+
+    #[test]
+    fn test_compare() {
+        let fast = Measurement::from_samples("fast", (), micros(&[10, 11, 10, 12, 11, 10, 11, 12]));
+        let slow = Measurement::from_samples("slow", (), micros(&[20, 21, 20, 22, 21, 20, 21, 22]));
+        let noisy = Measurement::from_samples("noisy", (), micros(&[5, 17, 9, 14, 11, 8, 16, 10]));
+
+        assert!(fast.compare(&slow).is_faster());
+        assert!(slow.compare(&fast).is_slower());
+        assert!(!noisy.compare(&fast).significant);
+    }
+
+    #[test]
+    fn test_scaling() {
+        let one = Measurement::from_samples("one", (), micros(&[40, 40, 40]));
+        let four = Measurement::from_samples("four", (), micros(&[10, 10, 10]));
+        assert_eq!(one.throughput(400), 10_000_000.0);
+        assert_eq!(format_si(one.throughput(400)), "10.00M");
+
+        let scaling = Scaling {
+            label: "test".to_string(),
+            items: 400,
+            measurements: vec![(1, one), (4, four)],
+        };
+        assert_eq!(scaling.speedup(4), Some(4.0));
+        assert_eq!(scaling.speedup(2), None);
+    }
+}
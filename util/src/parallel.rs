@@ -0,0 +1,120 @@
+use std::{
+    ops::Range,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+// Set by set_threads, 0 when not set
+static THREADS: AtomicUsize = AtomicUsize::new(0);
+
+// How many threads to split work over: set_threads, else AOC_THREADS, else one per core
+pub fn threads() -> usize {
+    match THREADS.load(Ordering::Relaxed) {
+        0 => std::env::var("AOC_THREADS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|&n| n > 0)
+            .unwrap_or_else(available),
+        n => n,
+    }
+}
+
+// Overrides the thread count for everything after it, 0 goes back to the default
+pub fn set_threads(n: usize) {
+    THREADS.store(n, Ordering::Relaxed);
+}
+
+pub fn available() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+// Powers of two up to the number of cores, plus that number, to measure scaling with
+pub fn thread_counts() -> Vec<usize> {
+    let max = available();
+    let mut counts = std::iter::successors(Some(1), |n| Some(n * 2))
+        .take_while(|&n| n < max)
+        .collect::<Vec<_>>();
+    counts.push(max);
+    counts
+}
+
+// Splits 0..len into one contiguous range per thread and runs f on each of them in parallel,
+// returning the results in order
+pub fn map_ranges<R: Send>(len: usize, f: impl Fn(Range<usize>) -> R + Sync) -> Vec<R> {
+    let threads = threads().clamp(1, len.max(1));
+    if threads == 1 {
+        return vec![f(0..len)];
+    }
+
+    let ranges = (0..threads).map(|i| len * i / threads..len * (i + 1) / threads);
+    thread::scope(|scope| {
+        let f = &f;
+        let handles = ranges
+            .map(|range| scope.spawn(move || f(range)))
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .map(|h| h.join().unwrap_or_else(|e| std::panic::resume_unwind(e)))
+            .collect()
+    })
+}
+
+// Like map_ranges, but hands each thread its chunk of items
+pub fn map_chunks<T: Sync, R: Send>(items: &[T], f: impl Fn(&[T]) -> R + Sync) -> Vec<R> {
+    map_ranges(items.len(), |range| f(&items[range]))
+}
+
+// Like map_chunks, for work that needs to modify its chunk
+pub fn map_chunks_mut<T: Send, R: Send>(
+    items: &mut [T],
+    f: impl Fn(&mut [T]) -> R + Sync,
+) -> Vec<R> {
+    let threads = threads().clamp(1, items.len().max(1));
+    if threads == 1 && !items.is_empty() {
+        return vec![f(items)];
+    }
+
+    let chunk_len = items.len().div_ceil(threads).max(1);
+    thread::scope(|scope| {
+        let f = &f;
+        let handles = items
+            .chunks_mut(chunk_len)
+            .map(|chunk| scope.spawn(move || f(chunk)))
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .map(|h| h.join().unwrap_or_else(|e| std::panic::resume_unwind(e)))
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_ranges() {
+        let items = (1..=1000).collect::<Vec<u64>>();
+        let sums = map_chunks(&items, |chunk| chunk.iter().sum::<u64>());
+        assert_eq!(sums.iter().sum::<u64>(), 500500);
+
+        let ranges = map_ranges(10, |range| range);
+        assert_eq!(ranges.first().unwrap().start, 0);
+        assert_eq!(ranges.last().unwrap().end, 10);
+        assert!(ranges.windows(2).all(|w| w[0].end == w[1].start));
+
+        let mut items = items;
+        map_chunks_mut(&mut items, |chunk| chunk.iter_mut().for_each(|i| *i *= 2));
+        assert_eq!(items.iter().sum::<u64>(), 1001000);
+        assert!(map_chunks_mut(&mut [0u8; 0], |c| c.len()).is_empty());
+
+        // One thread runs on the calling thread, without spawning
+        set_threads(1);
+        let caller = thread::current().id();
+        assert_eq!(
+            map_chunks_mut(&mut items, |_| thread::current().id()),
+            [caller]
+        );
+        set_threads(0);
+    }
+}
@@ -0,0 +1,214 @@
+use crate::ParseError;
+
+// Integers the cursor can scan, built up a decimal digit at a time
+pub trait Integer: Copy {
+    const ZERO: Self;
+    const SIGNED: bool;
+
+    // self * 10 + digit, or - digit when building a negative number, None on overflow
+    fn push_digit(self, digit: u8, negative: bool) -> Option<Self>;
+}
+
+macro_rules! impl_integer {
+    ($signed:literal: $($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                const ZERO: Self = 0;
+                const SIGNED: bool = $signed;
+
+                #[inline]
+                fn push_digit(self, digit: u8, negative: bool) -> Option<Self> {
+                    let shifted = self.checked_mul(10)?;
+                    if negative {
+                        shifted.checked_sub(digit as $t)
+                    } else {
+                        shifted.checked_add(digit as $t)
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(false: u8, u16, u32, u64, u128, usize);
+impl_integer!(true: i8, i16, i32, i64, i128, isize);
+
+// Scans through an input byte by byte without allocating, keeping track of where it is so
+// errors can point at the offending text
+#[derive(Debug, Clone)]
+pub struct Cursor<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Cursor { input, pos: 0 }
+    }
+
+    // Byte offset into the input
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    pub fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    pub fn is_at_end(&self) -> bool {
+        self.pos >= self.input.len()
+    }
+
+    pub fn peek(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.pos).copied()
+    }
+
+    // Skips bytes matching f, returning how many were skipped
+    pub fn skip_while(&mut self, f: impl Fn(u8) -> bool) -> usize {
+        let start = self.pos;
+        while self.peek().is_some_and(&f) {
+            self.pos += 1;
+        }
+        self.pos - start
+    }
+
+    // Spaces and tabs, but not line endings
+    pub fn skip_spaces(&mut self) -> usize {
+        self.skip_while(|b| b == b' ' || b == b'\t')
+    }
+
+    pub fn skip_whitespace(&mut self) -> usize {
+        self.skip_while(|b| b.is_ascii_whitespace())
+    }
+
+    // Consumes b if it is next
+    pub fn eat(&mut self, b: u8) -> bool {
+        let found = self.peek() == Some(b);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    pub fn eat_str(&mut self, s: &str) -> bool {
+        let found = self.rest().starts_with(s);
+        if found {
+            self.pos += s.len();
+        }
+        found
+    }
+
+    pub fn expect(&mut self, b: u8, reason: &str) -> Result<(), ParseError> {
+        match self.eat(b) {
+            true => Ok(()),
+            false => Err(self.error_here(reason)),
+        }
+    }
+
+    // Consumes a line ending, true if there was one to consume (or the input ended)
+    pub fn eat_line_end(&mut self) -> bool {
+        self.eat_str("\r\n") || self.eat(b'\n') || self.is_at_end()
+    }
+
+    // The rest of the current line without its line ending, moving on to the next line
+    pub fn line(&mut self) -> Option<&'a str> {
+        if self.is_at_end() {
+            return None;
+        }
+        let rest = self.rest();
+        let len = rest.find('\n').unwrap_or(rest.len());
+        self.pos += len;
+        self.eat(b'\n');
+
+        let line = &rest[..len];
+        Some(line.strip_suffix('\r').unwrap_or(line))
+    }
+
+    // Scans a decimal integer, with a leading - or + for signed types
+    pub fn integer<T: Integer>(&mut self) -> Result<T, ParseError> {
+        let start = self.pos;
+        let negative = T::SIGNED && self.eat(b'-');
+        if T::SIGNED && !negative {
+            self.eat(b'+');
+        }
+
+        let digits = self.pos;
+        let mut value = T::ZERO;
+        let mut overflow = false;
+        while let Some(b) = self.peek().filter(u8::is_ascii_digit) {
+            match value.push_digit(b - b'0', negative) {
+                Some(v) => value = v,
+                None => overflow = true,
+            }
+            self.pos += 1;
+        }
+
+        if self.pos == digits {
+            self.pos = start;
+            return Err(self.error_here("expected a number"));
+        }
+        if overflow {
+            let len = self.pos - start;
+            self.pos = start;
+            return Err(self.error(len, "number out of range"));
+        }
+        Ok(value)
+    }
+
+    // An error for the len bytes from the current position
+    pub fn error(&self, len: usize, reason: impl Into<String>) -> ParseError {
+        ParseError::at_offset(self.input, self.pos, len, reason)
+    }
+
+    // An error pointing at the next character, or the end of the input
+    pub fn error_here(&self, reason: impl Into<String>) -> ParseError {
+        let len = self.rest().chars().next().map_or(0, char::len_utf8);
+        self.error(len, reason)
+    }
+}
+
+// Parses the whole of s as an integer
+pub fn integer<T: Integer>(s: &str) -> Result<T, ParseError> {
+    let mut cursor = Cursor::new(s);
+    let value = cursor.integer()?;
+    match cursor.is_at_end() {
+        true => Ok(value),
+        false => Err(cursor.error_here("expected a number")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_integers() {
+        let mut cursor = Cursor::new("12 -34,+5\n255 256");
+        assert_eq!(cursor.integer::<u32>(), Ok(12));
+        assert_eq!(cursor.skip_spaces(), 1);
+        assert_eq!(cursor.integer::<i32>(), Ok(-34));
+        assert!(cursor.eat(b','));
+        assert_eq!(cursor.integer::<i64>(), Ok(5));
+        assert!(cursor.eat_line_end());
+        assert_eq!(cursor.integer::<u8>(), Ok(255));
+        cursor.skip_spaces();
+
+        let e = cursor.integer::<u8>().unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 5, "256"));
+        assert_eq!(cursor.position(), 14);
+
+        assert_eq!(integer::<i8>("-128"), Ok(i8::MIN));
+        assert!(integer::<u32>("-1").is_err());
+        assert!(integer::<u32>("12x").is_err());
+        assert!(integer::<u32>("").is_err());
+    }
+
+    #[test]
+    fn test_lines() {
+        let mut cursor = Cursor::new("ab\r\n\ncd");
+        assert_eq!(cursor.line(), Some("ab"));
+        assert_eq!(cursor.line(), Some(""));
+        assert_eq!(cursor.line(), Some("cd"));
+        assert_eq!(cursor.line(), None);
+    }
+}
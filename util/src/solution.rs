@@ -0,0 +1,270 @@
+use std::{error::Error, fmt, rc::Rc};
+
+use crate::{
+    day::Solver,
+    measure,
+    measure::{measure_scaling, Scaling},
+    parallel, Day, Measurement, ParseError,
+};
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub enum Answer {
+    Int(i64),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Int(i) => write!(f, "{}", i),
+            Answer::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+// Same as Display so measurements print the plain answer
+impl fmt::Debug for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+macro_rules! impl_answer_from_int {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(value: $t) -> Self {
+                Answer::Int(value as i64)
+            }
+        })*
+    };
+}
+
+impl_answer_from_int!(i32, u32, i64, u64, usize);
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Text(value.to_string())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Disagreement {
+    pub part: u8,
+    // The first variant's answer, which the others are checked against
+    pub reference: (&'static str, Answer),
+    pub diverged: Vec<(&'static str, Answer)>,
+}
+
+impl fmt::Display for Disagreement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (name, answer) = &self.reference;
+        writeln!(f, "Part {} variants disagree:", self.part)?;
+        write!(f, "  {:<24} {}   (reference)", name, answer)?;
+        for (name, answer) in &self.diverged {
+            write!(f, "\n- {:<24} {}", name, answer)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for Disagreement {}
+
+// Checks that every variant of one part produced the same answer as the first one
+pub fn check_agreement(part: u8, results: &[(&'static str, Answer)]) -> Result<(), Disagreement> {
+    let Some((reference, rest)) = results.split_first() else {
+        return Ok(());
+    };
+
+    let diverged = rest
+        .iter()
+        .filter(|(_, answer)| *answer != reference.1)
+        .cloned()
+        .collect::<Vec<_>>();
+
+    if diverged.is_empty() {
+        Ok(())
+    } else {
+        Err(Disagreement {
+            part,
+            reference: reference.clone(),
+            diverged,
+        })
+    }
+}
+
+pub struct Variant<I> {
+    pub part: u8,
+    pub name: &'static str,
+    pub label: &'static str,
+    pub run: Box<dyn Fn(&I) -> Answer>,
+}
+
+impl<I> Variant<I> {
+    pub fn new<T: Into<Answer>, F: Fn(&I) -> T + 'static>(
+        part: u8,
+        name: &'static str,
+        label: &'static str,
+        f: F,
+    ) -> Self {
+        Variant {
+            part,
+            name,
+            label,
+            run: Box::new(move |input| f(input).into()),
+        }
+    }
+}
+
+pub trait Solution: 'static {
+    const DAY: u8;
+
+    // Crate directory holding input.txt and the sample inputs, i.e. env!("CARGO_MANIFEST_DIR")
+    const DIR: &'static str;
+
+    type Input: 'static;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+
+    fn part1(input: &Self::Input) -> Answer;
+
+    fn part2(input: &Self::Input) -> Answer;
+
+    // Every implementation of each part, the first one registered for a part should be the one
+    // behind part1/part2
+    fn variants() -> Vec<Variant<Self::Input>> {
+        vec![
+            Variant::new(1, "default", "Part 1", Self::part1),
+            Variant::new(2, "default", "Part 2", Self::part2),
+        ]
+    }
+}
+
+impl<S: Solution + Sync> Day for S {
+    fn number(&self) -> u8 {
+        S::DAY
+    }
+
+    fn dir(&self) -> &'static str {
+        S::DIR
+    }
+
+    fn solvers<'a>(&self, input: &'a str) -> Result<Vec<Solver<'a>>, ParseError> {
+        let parsed = Rc::new(S::parse(input)?);
+
+        Ok(S::variants()
+            .into_iter()
+            .map(|v| {
+                let parsed = parsed.clone();
+                Solver::new(v.part, v.name, v.label, move || (v.run)(&parsed))
+            })
+            .collect())
+    }
+}
+
+// Parses the input once and measures every registered variant, panicking if any disagree
+pub fn bench<S: Solution>(input: &str) -> Vec<Measurement<Answer>> {
+    let parsed = match S::parse(input) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("Unable to parse input for day {}: {}", S::DAY, e);
+            std::process::exit(1);
+        }
+    };
+    // Parsing is part of every solve, so it gets measured too
+    measure("Parse input", || S::parse(input).is_ok());
+
+    let variants = S::variants();
+
+    let measurements = variants
+        .iter()
+        .map(|v| measure(v.label, || (v.run)(&parsed)))
+        .collect::<Vec<_>>();
+
+    let results = variants
+        .iter()
+        .zip(&measurements)
+        .map(|(v, m)| (v.part, v.name, m.result.clone()))
+        .collect::<Vec<_>>();
+    if let Err(e) = check_parts(&results) {
+        panic!("{e}");
+    }
+
+    measurements
+}
+
+// Measures how a parallel variant scales with the number of threads, items being the size of
+// the parsed input in whatever unit the variant splits it by
+pub fn bench_scaling<S: Solution>(
+    input: &str,
+    part: u8,
+    name: &str,
+    items: impl Fn(&S::Input) -> usize,
+) -> Scaling<Answer> {
+    let parsed = match S::parse(input) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("Unable to parse input for day {}: {}", S::DAY, e);
+            std::process::exit(1);
+        }
+    };
+    let variants = S::variants();
+    let variant = variants
+        .iter()
+        .find(|v| v.part == part && v.name == name)
+        .unwrap_or_else(|| panic!("Day {} has no part {part} variant {name}", S::DAY));
+
+    measure_scaling(
+        variant.label,
+        items(&parsed),
+        &parallel::thread_counts(),
+        || (variant.run)(&parsed),
+    )
+}
+
+// Groups (part, variant, answer) results by part and checks each group agrees
+pub fn check_parts(results: &[(u8, &'static str, Answer)]) -> Result<(), Disagreement> {
+    let mut parts = results.iter().map(|(part, _, _)| *part).collect::<Vec<_>>();
+    parts.sort();
+    parts.dedup();
+
+    for part in parts {
+        let for_part = results
+            .iter()
+            .filter(|(p, _, _)| *p == part)
+            .map(|(_, name, answer)| (*name, answer.clone()))
+            .collect::<Vec<_>>();
+        check_agreement(part, &for_part)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_agreement() {
+        assert!(check_agreement(1, &[]).is_ok());
+        assert!(check_agreement(1, &[("a", 1.into()), ("b", 1.into())]).is_ok());
+
+        let e = check_agreement(
+            2,
+            &[
+                ("naive", 514.into()),
+                ("bitmasks", 513.into()),
+                ("other", 514.into()),
+            ],
+        )
+        .unwrap_err();
+        assert_eq!(e.reference, ("naive", 514.into()));
+        assert_eq!(e.diverged, vec![("bitmasks", 513.into())]);
+        assert!(e.to_string().contains("- bitmasks"));
+    }
+}
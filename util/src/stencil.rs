@@ -0,0 +1,123 @@
+use crate::{geom::Point, Grid, ParseError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct StencilMatch {
+    // Top-left corner of the matched area
+    pub position: Point,
+    // Index into Stencil::orientations of the orientation that matched
+    pub orientation: usize,
+}
+
+// A small 2D pattern where None cells match anything, searched for in every rotation and
+// reflection
+#[derive(Debug, Clone)]
+pub struct Stencil {
+    orientations: Vec<Grid<Option<u8>>>,
+}
+
+impl Stencil {
+    // Parses one row per line, with the wildcard character matching any byte
+    pub fn parse(pattern: &str, wildcard: char) -> Result<Self, ParseError> {
+        let cells = Grid::parse(pattern, |c| match c {
+            c if c == wildcard => Some(None),
+            c if c.is_ascii() => Some(Some(c as u8)),
+            _ => None,
+        })?;
+        Ok(Self::new(cells))
+    }
+
+    pub fn new(cells: Grid<Option<u8>>) -> Self {
+        let mut orientations: Vec<Grid<Option<u8>>> = vec![];
+        for flipped in [cells.clone(), cells.flip_horizontal()] {
+            let mut rotated = flipped;
+            for _ in 0..4 {
+                // Symmetric patterns come back to an orientation we already have
+                if !orientations.contains(&rotated) {
+                    orientations.push(rotated.clone());
+                }
+                rotated = rotated.rotate_right();
+            }
+        }
+        Stencil { orientations }
+    }
+
+    // The distinct rotations and reflections, starting with the pattern as given
+    pub fn orientations(&self) -> &[Grid<Option<u8>>] {
+        &self.orientations
+    }
+
+    pub fn find_all(&self, grid: &Grid<u8>) -> Vec<StencilMatch> {
+        let mut matches = vec![];
+        self.for_each_match(grid, |m| matches.push(m));
+        matches
+    }
+
+    pub fn count(&self, grid: &Grid<u8>) -> usize {
+        let mut count = 0;
+        self.for_each_match(grid, |_| count += 1);
+        count
+    }
+
+    pub fn for_each_match(&self, grid: &Grid<u8>, mut f: impl FnMut(StencilMatch)) {
+        for (orientation, cells) in self.orientations.iter().enumerate() {
+            if cells.width > grid.width || cells.height > grid.height {
+                continue;
+            }
+
+            // Only the fixed cells need checking
+            let fixed = cells
+                .positions()
+                .filter_map(|(x, y)| cells[(x, y)].map(|b| (x, y, b)))
+                .collect::<Vec<_>>();
+
+            for y in 0..=grid.height - cells.height {
+                for x in 0..=grid.width - cells.width {
+                    if fixed.iter().all(|&(dx, dy, b)| grid[(x + dx, y + dy)] == b) {
+                        f(StencilMatch {
+                            position: Point::from_index((x, y)),
+                            orientation,
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_orientations() {
+        let x_mas = Stencil::parse("M.S\n.A.\nM.S", '.').unwrap();
+        assert_eq!(x_mas.orientations().len(), 4);
+
+        let plus = Stencil::parse(".A.\nAAA\n.A.", '.').unwrap();
+        assert_eq!(plus.orientations().len(), 1);
+
+        let ell = Stencil::parse("A.\nAB", '.').unwrap();
+        assert_eq!(ell.orientations().len(), 8);
+    }
+
+    #[test]
+    fn test_find_all() {
+        let grid = Grid::parse_bytes("MXS.\n.AM.\nMXSS\n").unwrap();
+        let x_mas = Stencil::parse("M.S\n.A.\nM.S", '.').unwrap();
+        assert_eq!(
+            x_mas.find_all(&grid),
+            vec![StencilMatch {
+                position: Point::new(0, 0),
+                orientation: 0
+            }]
+        );
+
+        let spaced = Stencil::parse("M S\n A \nM S", ' ').unwrap();
+        assert_eq!(spaced.orientations(), x_mas.orientations());
+        assert_eq!(spaced.count(&grid), 1);
+
+        let pair = Stencil::parse("SS", '.').unwrap();
+        assert_eq!(pair.count(&grid), 1);
+        assert_eq!(pair.find_all(&grid)[0].position, Point::new(2, 2));
+    }
+}
@@ -0,0 +1,82 @@
+use std::{error::Error, fmt, io, io::BufRead};
+
+use crate::ParseError;
+
+// Reading a streamed input can fail on either the reading or the parsing
+#[derive(Debug)]
+pub enum StreamError {
+    Io(io::Error),
+    Parse(ParseError),
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamError::Io(e) => write!(f, "{e}"),
+            StreamError::Parse(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl Error for StreamError {}
+
+impl From<io::Error> for StreamError {
+    fn from(e: io::Error) -> Self {
+        StreamError::Io(e)
+    }
+}
+
+impl From<ParseError> for StreamError {
+    fn from(e: ParseError) -> Self {
+        StreamError::Parse(e)
+    }
+}
+
+// Calls f with each line, without its line ending, and its 1-based number. One buffer is reused
+// for all lines, so memory only grows with the longest line rather than the whole input. Errors
+// from f are relative to the line they were given and get moved to its position.
+pub fn for_each_line<R: BufRead>(
+    mut reader: R,
+    mut f: impl FnMut(usize, &str) -> Result<(), ParseError>,
+) -> Result<(), StreamError> {
+    let mut buffer = String::new();
+    let mut number = 0;
+    loop {
+        buffer.clear();
+        if reader.read_line(&mut buffer)? == 0 {
+            return Ok(());
+        }
+        number += 1;
+
+        let line = buffer.strip_suffix('\n').unwrap_or(&buffer);
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        f(number, line).map_err(|e| e.on_line(number))?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_for_each_line() {
+        let input = "1 2\r\n3 4\n\n5 x";
+        let mut lines = vec![];
+        let result = for_each_line(input.as_bytes(), |n, line| {
+            lines.push((n, line.to_string()));
+            match line.find('x') {
+                Some(i) => Err(ParseError::at_offset(line, i, 1, "expected a number")),
+                None => Ok(()),
+            }
+        });
+
+        assert_eq!(
+            lines,
+            [(1, "1 2"), (2, "3 4"), (3, ""), (4, "5 x")].map(|(n, l)| (n, l.to_string()))
+        );
+        match result {
+            Err(StreamError::Parse(e)) => assert_eq!((e.line, e.column), (4, 3)),
+            other => panic!("Expected a parse error, got {other:?}"),
+        }
+    }
+}
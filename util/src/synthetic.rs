@@ -0,0 +1,41 @@
+// Size of the synthetic input asked for with AOC_SYNTHETIC (e.g. AOC_SYNTHETIC=10000), for days
+// that can generate their own inputs to benchmark with
+pub fn size_from_env() -> Option<usize> {
+    let value = std::env::var("AOC_SYNTHETIC").ok()?;
+    match value.trim().replace('_', "").parse() {
+        Ok(size) => Some(size),
+        Err(_) => {
+            eprintln!("Ignoring AOC_SYNTHETIC={value:?}, expected a size");
+            None
+        }
+    }
+}
+
+// Small xorshift generator, so synthetic inputs are reproducible from a seed
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // Zero would stay zero forever
+        Rng((seed ^ 0x9e37_79b9_7f4a_7c15).max(1))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+
+    // Uniform enough in 0..n for generating inputs
+    pub fn below(&mut self, n: u64) -> u64 {
+        ((self.next_u64() as u128 * n as u128) >> 64) as u64
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len() as u64) as usize]
+    }
+}
@@ -0,0 +1,67 @@
+use std::{fs, path::Path};
+
+use crate::{Answer, Solution};
+
+// Runs every variant of the listed parts on a file from the day's directory, panicking with the
+// variants that got the wrong answer
+pub fn check_answers<S: Solution>(file: &str, expected: &[(u8, Answer)]) {
+    let path = Path::new(S::DIR).join(file);
+    let input = fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("Unable to read {}: {}", path.display(), e));
+
+    let parsed =
+        S::parse(&input).unwrap_or_else(|e| panic!("Unable to parse {}: {}", path.display(), e));
+    let variants = S::variants();
+
+    let mut failures = vec![];
+    for (part, expected) in expected {
+        let for_part = variants
+            .iter()
+            .filter(|v| v.part == *part)
+            .collect::<Vec<_>>();
+        assert!(
+            !for_part.is_empty(),
+            "No variants registered for part {part}"
+        );
+
+        for v in for_part {
+            let answer = (v.run)(&parsed);
+            if answer != *expected {
+                failures.push(format!(
+                    "  part {} [{}]: expected {}, got {}",
+                    part, v.name, expected, answer
+                ));
+            }
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "Wrong answers for day {} {}:\n{}",
+        S::DAY,
+        file,
+        failures.join("\n")
+    );
+}
+
+// Generates a test per input file that checks every variant gets the expected answers, e.g.
+//
+// util::answer_tests! {
+//     Day4,
+//     sample: "input_sample_part1.txt" => [1: 18, 2: 9],
+//     input: "input.txt" => [1: 2397, 2: 1824],
+// }
+#[macro_export]
+macro_rules! answer_tests {
+    ($solution:ty, $($name:ident: $file:literal => [$($part:literal: $expected:expr),* $(,)?]),* $(,)?) => {
+        $(
+            #[test]
+            fn $name() {
+                $crate::testing::check_answers::<$solution>(
+                    $file,
+                    &[$(($part, $crate::Answer::from($expected))),*],
+                );
+            }
+        )*
+    };
+}
@@ -0,0 +1,298 @@
+use crate::{
+    geom::{Direction, Point},
+    Grid,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Wrap {
+    // Words stop at the edges of the grid
+    Bounded,
+    // Words continue on the opposite edge, as if the grid was a torus
+    Torus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct WordMatch {
+    pub start: Point,
+    pub direction: Direction,
+    // Index of the matched word in the dictionary
+    pub needle: usize,
+}
+
+// Finds every word of a dictionary along the given directions of a grid, scanning each line of
+// the grid once with an Aho-Corasick automaton instead of once per word
+pub struct WordSearch {
+    needles: Vec<Vec<u8>>,
+    directions: Vec<Direction>,
+    wrap: Wrap,
+    automaton: Automaton,
+}
+
+impl WordSearch {
+    pub fn new<N: AsRef<[u8]>>(needles: &[N], directions: &[Direction], wrap: Wrap) -> Self {
+        let needles = needles
+            .iter()
+            .map(|n| n.as_ref().to_vec())
+            .collect::<Vec<_>>();
+        assert!(needles.iter().all(|n| !n.is_empty()), "Empty needle");
+
+        WordSearch {
+            automaton: Automaton::new(&needles),
+            needles,
+            directions: directions.to_vec(),
+            wrap,
+        }
+    }
+
+    pub fn needle(&self, index: usize) -> &[u8] {
+        &self.needles[index]
+    }
+
+    pub fn find_all(&self, grid: &Grid<u8>) -> Vec<WordMatch> {
+        let mut matches = vec![];
+        self.for_each_match(grid, |m| matches.push(m));
+        matches
+    }
+
+    pub fn count(&self, grid: &Grid<u8>) -> usize {
+        let mut count = 0;
+        self.for_each_match(grid, |_| count += 1);
+        count
+    }
+
+    pub fn for_each_match(&self, grid: &Grid<u8>, mut f: impl FnMut(WordMatch)) {
+        let mut line = vec![];
+        for &direction in &self.directions {
+            match self.wrap {
+                Wrap::Bounded => {
+                    for start in line_starts(grid, direction) {
+                        collect_line(grid, start, direction, &mut line);
+                        self.scan(grid, &line, line.len(), direction, &mut f);
+                    }
+                }
+                Wrap::Torus => {
+                    let mut visited = vec![false; grid.entries.len()];
+                    for start in grid.positions() {
+                        if visited[start.1 * grid.width + start.0] {
+                            continue;
+                        }
+                        collect_cycle(grid, Point::from_index(start), direction, &mut line);
+                        for p in &line {
+                            visited[p.y as usize * grid.width + p.x as usize] = true;
+                        }
+
+                        // Repeat the start of the cycle so words can run across its end
+                        let cycle_len = line.len();
+                        let longest = self.needles.iter().map(Vec::len).max().unwrap_or(1);
+                        for i in 0..longest - 1 {
+                            line.push(line[i % cycle_len]);
+                        }
+                        self.scan(grid, &line, cycle_len, direction, &mut f);
+                    }
+                }
+            }
+        }
+    }
+
+    // Reports matches along line, ignoring those starting at or after max_start
+    fn scan(
+        &self,
+        grid: &Grid<u8>,
+        line: &[Point],
+        max_start: usize,
+        direction: Direction,
+        f: &mut impl FnMut(WordMatch),
+    ) {
+        let mut state = 0;
+        for (i, p) in line.iter().enumerate() {
+            state = self.automaton.next(state, grid[*p]);
+            for &needle in self.automaton.outputs(state) {
+                let start = i + 1 - self.needles[needle].len();
+                if start < max_start {
+                    f(WordMatch {
+                        start: line[start],
+                        direction,
+                        needle,
+                    });
+                }
+            }
+        }
+    }
+}
+
+// Cells whose predecessor in direction is outside the grid, i.e. where lines start
+fn line_starts(grid: &Grid<u8>, direction: Direction) -> impl Iterator<Item = Point> + '_ {
+    grid.positions()
+        .map(Point::from_index)
+        .filter(move |p| grid.get_point(*p - direction.delta()).is_none())
+}
+
+fn collect_line(grid: &Grid<u8>, start: Point, direction: Direction, line: &mut Vec<Point>) {
+    line.clear();
+    let mut next = Some(start);
+    while let Some(p) = next {
+        line.push(p);
+        next = grid.step(p, direction);
+    }
+}
+
+fn collect_cycle(grid: &Grid<u8>, start: Point, direction: Direction, line: &mut Vec<Point>) {
+    line.clear();
+    let (width, height) = (grid.width as isize, grid.height as isize);
+    let mut p = start;
+    loop {
+        line.push(p);
+        let next = p.step(direction);
+        p = Point::new(next.x.rem_euclid(width), next.y.rem_euclid(height));
+        if p == start {
+            break;
+        }
+    }
+}
+
+// Aho-Corasick automaton with a dense transition table over the bytes used by the needles
+struct Automaton {
+    // Maps each byte to its column in the table, 0 for bytes that are in no needle
+    classes: [u16; 256],
+    stride: usize,
+    transitions: Vec<u32>,
+    outputs: Vec<Vec<usize>>,
+}
+
+impl Automaton {
+    fn new(needles: &[Vec<u8>]) -> Self {
+        let mut classes = [0u16; 256];
+        let mut stride = 1;
+        for &b in needles.iter().flatten() {
+            if classes[b as usize] == 0 {
+                classes[b as usize] = stride as u16;
+                stride += 1;
+            }
+        }
+
+        // Build the trie, 0 is the root and also means no transition yet
+        let mut transitions = vec![0u32; stride];
+        let mut outputs = vec![vec![]];
+        for (index, needle) in needles.iter().enumerate() {
+            let mut state = 0;
+            for &b in needle {
+                let slot = state * stride + classes[b as usize] as usize;
+                if transitions[slot] == 0 {
+                    transitions[slot] = outputs.len() as u32;
+                    transitions.extend(std::iter::repeat_n(0, stride));
+                    outputs.push(vec![]);
+                }
+                state = transitions[slot] as usize;
+            }
+            outputs[state].push(index);
+        }
+
+        // Breadth first, fill in the missing transitions from the failure links so that every
+        // state has a transition for every class
+        let mut fail = vec![0usize; outputs.len()];
+        let mut queue = std::collections::VecDeque::new();
+        queue.extend(
+            transitions[1..stride]
+                .iter()
+                .filter(|&&child| child != 0)
+                .map(|&child| child as usize),
+        );
+
+        while let Some(state) = queue.pop_front() {
+            for class in 1..stride {
+                let slot = state * stride + class;
+                let child = transitions[slot] as usize;
+                let fallback = transitions[fail[state] * stride + class];
+                if child == 0 {
+                    transitions[slot] = fallback;
+                } else {
+                    fail[child] = fallback as usize;
+                    let inherited = outputs[fallback as usize].clone();
+                    outputs[child].extend(inherited);
+                    queue.push_back(child);
+                }
+            }
+        }
+
+        Automaton {
+            classes,
+            stride,
+            transitions,
+            outputs,
+        }
+    }
+
+    fn next(&self, state: usize, b: u8) -> usize {
+        self.transitions[state * self.stride + self.classes[b as usize] as usize] as usize
+    }
+
+    fn outputs(&self, state: usize) -> &[usize] {
+        &self.outputs[state]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_search() {
+        let grid = Grid::parse_bytes("CATS\nXDOG\nCATX\n").unwrap();
+        let search = WordSearch::new(
+            &["CAT", "AT", "DOG", "GOD", "TAC"],
+            &Direction::ALL,
+            Wrap::Bounded,
+        );
+
+        let mut found = search
+            .find_all(&grid)
+            .iter()
+            .map(|m| (search.needle(m.needle).to_vec(), m.start, m.direction))
+            .collect::<Vec<_>>();
+        found.sort();
+
+        let word = |w: &str, x, y, d| (w.as_bytes().to_vec(), Point::new(x, y), d);
+        let mut expected = vec![
+            word("AT", 1, 0, Direction::Right),
+            word("AT", 1, 2, Direction::Right),
+            word("CAT", 0, 0, Direction::Right),
+            word("CAT", 0, 2, Direction::Right),
+            word("DOG", 1, 1, Direction::Right),
+            word("GOD", 3, 1, Direction::Left),
+            word("TAC", 2, 0, Direction::Left),
+            word("TAC", 2, 2, Direction::Left),
+        ];
+        expected.sort();
+        assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn test_wrap() {
+        let grid = Grid::parse_bytes("GDO\nABC\n").unwrap();
+        let search = WordSearch::new(&["DOG"], &[Direction::Right], Wrap::Torus);
+        assert_eq!(
+            search.find_all(&grid),
+            vec![WordMatch {
+                start: Point::new(1, 0),
+                direction: Direction::Right,
+                needle: 0
+            }]
+        );
+
+        let search = WordSearch::new(&["DOG"], &[Direction::Right], Wrap::Bounded);
+        assert_eq!(search.count(&grid), 0);
+    }
+
+    #[test]
+    fn test_every_byte() {
+        let needles = (0..=255u8)
+            .map(|b| [b, b.wrapping_add(1)])
+            .collect::<Vec<_>>();
+        let grid = Grid::from_vec(256, 1, (0..=255u8).collect());
+        let search = WordSearch::new(&needles, &[Direction::Right], Wrap::Bounded);
+        assert_eq!(search.count(&grid), 255);
+
+        let search = WordSearch::new(&needles, &[Direction::Right], Wrap::Torus);
+        assert_eq!(search.count(&grid), 256);
+    }
+}